@@ -0,0 +1,480 @@
+//! Interfaz de línea de comandos sin ventana
+//!
+//! Ejecuta el mismo pipeline que la aplicación de escritorio:
+//!
+//! ```text
+//! audioink-cli [OPTIONS] <FILE|URL>...
+//! ```
+
+use audioink_rs_lib::core::{
+    is_model_downloaded, list_all_models, set_model_sources, CancellationToken, ModelSources,
+};
+use audioink_rs_lib::models::{
+    DecodingOptions, DecodingStrategy, HallucinationFilterMode, JobSource, ProgressEvent, Task,
+    TranscribeOptions, TranscriptionResult,
+};
+use audioink_rs_lib::persistence::{HistoryManager, SettingsManager};
+use audioink_rs_lib::service::{ProgressReporter, TranscriptionRequest, TranscriptionService};
+use audioink_rs_lib::utils::{segments_to_srt, segments_to_vtt, AudioInkError};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+const USAGE: &str = "\
+Usage: audioink-cli [OPTIONS] <FILE|URL>...
+
+Options:
+  -m, --model <NAME>      Whisper model, e.g. base, small.en, medium-q5_0 (see --list-models) [default: base]
+  -l, --language <CODE>   Language code or \"auto\" [default: auto]
+      --language-per-chunk
+                          Detect the language of every 30s chunk (mixed-language audio)
+  -t, --timestamps        Prefix each line with its timestamp
+      --words             Compute word-level timestamps
+      --translate         Translate the speech to English
+      --vad               Skip silence before transcribing
+  -p, --prompt <TEXT>     Initial prompt or glossary of names and terms
+      --overlap <SECS>    Audio repeated between chunks of long files [default: 0]
+      --decoding <PRESET> Decoding preset: default, fast, accurate [default: default]
+      --beam-size <N>     Use beam search with N beams
+      --temperature <T>   Initial sampling temperature (0.0 - 1.0) [default: 0.0]
+      --hallucinations <MODE>
+                          Repetition/silence filter: off, flag, drop [default: off]
+  -w, --workers <N>       Chunks of long files transcribed at once [default: from settings]
+      --threads <N>       Whisper threads per chunk, 0 = auto [default: from settings]
+  -s, --speed <FACTOR>    Speed up audio before transcribing (1.0 - 2.0) [default: 1.0]
+  -f, --format <FORMAT>   Output format: txt, srt, vtt, json [default: txt]
+  -o, --output <PATH>     Write the output to a file (or a directory for several inputs)
+      --no-history        Don't save the transcription to the history
+  -q, --quiet             Don't print progress to stderr
+      --list-models       List models and whether they are downloaded
+  -h, --help              Print this help";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Txt,
+    Srt,
+    Vtt,
+    Json,
+}
+
+impl OutputFormat {
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "txt" | "text" => Some(OutputFormat::Txt),
+            "srt" => Some(OutputFormat::Srt),
+            "vtt" | "webvtt" => Some(OutputFormat::Vtt),
+            "json" => Some(OutputFormat::Json),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Txt => "txt",
+            OutputFormat::Srt => "srt",
+            OutputFormat::Vtt => "vtt",
+            OutputFormat::Json => "json",
+        }
+    }
+
+    fn render(&self, result: &TranscriptionResult) -> Result<String, String> {
+        match self {
+            OutputFormat::Txt => Ok(format!("{}\n", result.text)),
+            OutputFormat::Srt => Ok(segments_to_srt(&result.segments)),
+            OutputFormat::Vtt => Ok(segments_to_vtt(&result.segments)),
+            OutputFormat::Json => serde_json::to_string_pretty(result).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+struct CliArgs {
+    inputs: Vec<String>,
+    options: TranscribeOptions,
+    format: OutputFormat,
+    output: Option<PathBuf>,
+    save_to_history: bool,
+    quiet: bool,
+    list_models: bool,
+    workers: Option<usize>,
+    threads: Option<usize>,
+}
+
+enum ParsedArgs {
+    Run(Box<CliArgs>),
+    Help,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<ParsedArgs, String> {
+    let mut parsed = CliArgs {
+        inputs: Vec::new(),
+        options: TranscribeOptions::default(),
+        format: OutputFormat::Txt,
+        output: None,
+        save_to_history: true,
+        quiet: false,
+        list_models: false,
+        workers: None,
+        threads: None,
+    };
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("Missing value for {}", name));
+
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(ParsedArgs::Help),
+            "-m" | "--model" => parsed.options.model = value(&arg)?,
+            "-l" | "--language" => parsed.options.language = value(&arg)?,
+            "--language-per-chunk" => parsed.options.language_per_chunk = true,
+            "-t" | "--timestamps" => parsed.options.include_timestamps = true,
+            "--words" => parsed.options.word_timestamps = true,
+            "--translate" => parsed.options.task = Task::Translate,
+            "--vad" => parsed.options.vad = true,
+            "-s" | "--speed" => {
+                let raw = value(&arg)?;
+                parsed.options.speed = raw
+                    .parse()
+                    .map_err(|_| format!("Invalid speed: {}", raw))?;
+            }
+            "-p" | "--prompt" => parsed.options.initial_prompt = Some(value(&arg)?),
+            "--overlap" => {
+                let raw = value(&arg)?;
+                parsed.options.chunk_overlap_secs = raw
+                    .parse()
+                    .map_err(|_| format!("Invalid overlap: {}", raw))?;
+            }
+            "--decoding" => {
+                let raw = value(&arg)?;
+                let preset = DecodingOptions::preset(&raw)
+                    .ok_or_else(|| format!("Unknown decoding preset: {}", raw))?;
+                // Un perfil reinicia la decodificación; los flags posteriores se siguen aplicando
+                parsed.options.decoding = preset;
+            }
+            "--beam-size" => {
+                let raw = value(&arg)?;
+                let beam_size = raw
+                    .parse()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| format!("Invalid beam size: {}", raw))?;
+                parsed.options.decoding.strategy = DecodingStrategy::Beam { beam_size };
+            }
+            "--temperature" => {
+                let raw = value(&arg)?;
+                parsed.options.decoding.temperature = raw
+                    .parse()
+                    .ok()
+                    .filter(|t| (0.0..=1.0).contains(t))
+                    .ok_or_else(|| format!("Invalid temperature (0.0 - 1.0): {}", raw))?;
+            }
+            "--hallucinations" => {
+                let raw = value(&arg)?;
+                parsed.options.hallucination_filter.mode = match raw.to_lowercase().as_str() {
+                    "off" => HallucinationFilterMode::Off,
+                    "flag" => HallucinationFilterMode::Flag,
+                    "drop" => HallucinationFilterMode::Drop,
+                    _ => return Err(format!("Unknown hallucination filter mode: {}", raw)),
+                };
+            }
+            "-w" | "--workers" => {
+                let raw = value(&arg)?;
+                parsed.workers = Some(raw.parse().map_err(|_| format!("Invalid worker count: {}", raw))?);
+            }
+            "--threads" => {
+                let raw = value(&arg)?;
+                parsed.threads = Some(raw.parse().map_err(|_| format!("Invalid thread count: {}", raw))?);
+            }
+            "-f" | "--format" => {
+                let raw = value(&arg)?;
+                parsed.format =
+                    OutputFormat::parse(&raw).ok_or_else(|| format!("Unknown format: {}", raw))?;
+            }
+            "-o" | "--output" => parsed.output = Some(PathBuf::from(value(&arg)?)),
+            "--no-history" => parsed.save_to_history = false,
+            "-q" | "--quiet" => parsed.quiet = true,
+            "--list-models" => parsed.list_models = true,
+            other if other.starts_with('-') && other.len() > 1 => {
+                return Err(format!("Unknown option: {}", other));
+            }
+            input => parsed.inputs.push(input.to_string()),
+        }
+    }
+
+    Ok(ParsedArgs::Run(Box::new(parsed)))
+}
+
+/// Escribe el progreso del pipeline en stderr
+struct ConsoleReporter {
+    label: String,
+}
+
+impl ProgressReporter for ConsoleReporter {
+    fn report(&self, event: ProgressEvent) {
+        let mut stderr = std::io::stderr().lock();
+        let _ = match event {
+            ProgressEvent::Started { message } => writeln!(stderr, "[{}] {}", self.label, message),
+            ProgressEvent::Progress { progress, message, .. } => writeln!(
+                stderr,
+                "[{}] {:>3.0}% {}",
+                self.label,
+                progress * 100.0,
+                message
+            ),
+            ProgressEvent::Completed { message } | ProgressEvent::Cancelled { message } => {
+                writeln!(stderr, "[{}] {}", self.label, message)
+            }
+            _ => Ok(()),
+        };
+    }
+}
+
+fn print_models() {
+    for model in list_all_models() {
+        let status = if is_model_downloaded(&model) {
+            "downloaded"
+        } else {
+            "not downloaded"
+        };
+        println!(
+            "{:<20} {:>8} {:<16} {}",
+            model.to_string(),
+            format!("{} MB", model.size_bytes() / 1_000_000),
+            status,
+            model.description()
+        );
+    }
+}
+
+/// Dónde escribir la salida de una entrada; `None` es stdout
+fn output_path(args: &CliArgs, source_name: &str) -> Option<PathBuf> {
+    let output = args.output.as_ref()?;
+    if args.inputs.len() > 1 || output.is_dir() {
+        Some(output.join(format!("{}.{}", source_name, args.format.extension())))
+    } else {
+        Some(output.clone())
+    }
+}
+
+/// Nombre base de la salida de una entrada: el nombre del archivo sin extensión
+fn source_name(source: &JobSource, index: usize) -> String {
+    match source {
+        JobSource::File { path } => PathBuf::from(path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("audio")
+            .to_string(),
+        JobSource::Url { .. } => format!("url-{}", index + 1),
+    }
+}
+
+/// Nombres de salida sin repetir para todas las entradas
+///
+/// Entradas con el mismo nombre (`a/talk.mp3` y `b/talk.mp3`) se escribirían
+/// en el mismo archivo de `-o <dir>`; las repetidas reciben `-2`, `-3`...
+/// La comparación ignora mayúsculas por los sistemas de archivos que también lo hacen.
+fn output_names(sources: &[JobSource]) -> Vec<String> {
+    let mut used = HashSet::new();
+    sources
+        .iter()
+        .enumerate()
+        .map(|(index, source)| {
+            let base = source_name(source, index);
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while !used.insert(name.to_lowercase()) {
+                name = format!("{}-{}", base, suffix);
+                suffix += 1;
+            }
+            name
+        })
+        .collect()
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(ParsedArgs::Run(args)) => *args,
+        Ok(ParsedArgs::Help) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    // Mismo mirror y directorios extra de modelos que la aplicación
+    let mut settings = SettingsManager::new().get();
+    set_model_sources(ModelSources::from(&settings));
+
+    if args.list_models {
+        print_models();
+        return ExitCode::SUCCESS;
+    }
+
+    if args.inputs.is_empty() {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    }
+
+    if args.inputs.len() > 1 {
+        if let Some(ref output) = args.output {
+            if let Err(e) = std::fs::create_dir_all(output) {
+                eprintln!("Cannot create {}: {}", output.display(), e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    // Ctrl-C cancela la transcripción en curso y detiene el lote
+    let cancel = CancellationToken::new();
+    let ctrl_c_token = cancel.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            ctrl_c_token.cancel();
+        }
+    });
+
+    // Los flags sustituyen a los ajustes guardados solo en esta ejecución
+    if let Some(workers) = args.workers {
+        settings.chunk_workers = workers;
+    }
+    if let Some(threads) = args.threads {
+        settings.whisper_threads = threads;
+    }
+    let service = TranscriptionService::new(
+        HistoryManager::new(),
+        Arc::new(SettingsManager::in_memory(settings)),
+    );
+    let mut failed = false;
+
+    let sources: Vec<JobSource> = args.inputs.iter().map(|input| JobSource::parse(input)).collect();
+    let names = output_names(&sources);
+
+    for ((input, source), name) in args.inputs.iter().zip(sources).zip(names) {
+        let reporter: Arc<dyn ProgressReporter> = if args.quiet {
+            Arc::new(audioink_rs_lib::service::NoopReporter)
+        } else {
+            Arc::new(ConsoleReporter { label: name.clone() })
+        };
+
+        let mut request = TranscriptionRequest::new(source, args.options.clone());
+        request.save_to_history = args.save_to_history;
+
+        let result = match service.transcribe(&request, reporter, &cancel).await {
+            Ok(result) => result,
+            Err(AudioInkError::Cancelled) => {
+                eprintln!("Cancelled");
+                return ExitCode::from(130);
+            }
+            Err(e) => {
+                eprintln!("{}: {}", input, e);
+                failed = true;
+                continue;
+            }
+        };
+
+        let rendered = match args.format.render(&result) {
+            Ok(rendered) => rendered,
+            Err(e) => {
+                eprintln!("{}: {}", input, e);
+                failed = true;
+                continue;
+            }
+        };
+
+        match output_path(&args, &name) {
+            Some(path) => {
+                if let Err(e) = std::fs::write(&path, rendered) {
+                    eprintln!("Cannot write {}: {}", path.display(), e);
+                    failed = true;
+                }
+            }
+            None => print!("{}", rendered),
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, String> {
+        match parse_args(args.iter().map(|a| a.to_string()))? {
+            ParsedArgs::Run(args) => Ok(*args),
+            ParsedArgs::Help => Err("help".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse_args_options_and_inputs() {
+        let args = parse(&[
+            "-m", "small.en", "--language", "es", "-t", "--words", "--translate", "--vad",
+            "-s", "1.5", "--overlap", "2", "-f", "srt", "-o", "out", "--no-history", "-q",
+            "-w", "3", "--threads", "4", "a.mp3", "https://youtu.be/x",
+        ])
+        .unwrap();
+
+        assert_eq!(args.inputs, vec!["a.mp3", "https://youtu.be/x"]);
+        assert_eq!(args.options.model, "small.en");
+        assert_eq!(args.options.language, "es");
+        assert!(args.options.include_timestamps);
+        assert!(args.options.word_timestamps);
+        assert_eq!(args.options.task, Task::Translate);
+        assert!(args.options.vad);
+        assert_eq!(args.options.speed, 1.5);
+        assert_eq!(args.options.chunk_overlap_secs, 2.0);
+        assert_eq!(args.format, OutputFormat::Srt);
+        assert_eq!(args.output, Some(PathBuf::from("out")));
+        assert!(!args.save_to_history);
+        assert!(args.quiet);
+        assert_eq!(args.workers, Some(3));
+        assert_eq!(args.threads, Some(4));
+    }
+
+    #[test]
+    fn test_parse_args_decoding_flags_after_preset() {
+        let args = parse(&["--decoding", "fast", "--beam-size", "4", "--temperature", "0.4", "a.mp3"])
+            .unwrap();
+        assert_eq!(args.options.decoding.strategy, DecodingStrategy::Beam { beam_size: 4 });
+        assert_eq!(args.options.decoding.temperature, 0.4);
+
+        assert!(parse(&["--decoding", "slow"]).is_err());
+        assert!(parse(&["--beam-size", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_rejects_invalid_values() {
+        assert!(parse(&["--temperature", "1.5"]).is_err());
+        assert!(parse(&["--temperature", "-0.1"]).is_err());
+        assert!(parse(&["--temperature", "hot"]).is_err());
+        assert!(parse(&["--speed", "fast"]).is_err());
+        assert!(parse(&["--hallucinations", "maybe"]).is_err());
+        assert!(parse(&["--format", "docx"]).is_err());
+        assert!(parse(&["--unknown"]).is_err());
+        assert!(parse(&["--model"]).is_err());
+        assert_eq!(parse(&["a.mp3", "--help"]).err().as_deref(), Some("help"));
+    }
+
+    #[test]
+    fn test_output_names_disambiguate_same_stem() {
+        let sources: Vec<JobSource> = ["a/talk.mp3", "b/talk.mp3", "c/Talk.wav", "https://youtu.be/x", "url-4.mp3"]
+            .iter()
+            .map(|input| JobSource::parse(input))
+            .collect();
+
+        assert_eq!(
+            output_names(&sources),
+            vec!["talk", "talk-2", "Talk-3", "url-4", "url-4-2"]
+        );
+    }
+}
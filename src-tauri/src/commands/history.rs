@@ -1,5 +1,6 @@
 use crate::commands::transcription::AppState;
 use crate::models::TranscriptionEntry;
+use crate::utils::{write_subtitles, SubtitleFormat};
 use tauri::State;
 
 /// Obtiene todo el historial de transcripciones
@@ -63,3 +64,42 @@ pub async fn get_history_count(
         .await
         .map_err(|e| e.to_string())
 }
+
+/// Exporta una transcripción del historial como subtítulos SRT o WebVTT
+#[tauri::command]
+pub async fn export_transcription_subtitles(
+    state: State<'_, AppState>,
+    id: String,
+    format: String,
+    output_path: String,
+) -> Result<String, String> {
+    let format = SubtitleFormat::parse(&format)
+        .ok_or_else(|| format!("Formato de subtítulos desconocido: {}", format))?;
+
+    let entry = state
+        .history_manager
+        .get_transcription(&id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Transcripción no encontrada: {}", id))?;
+
+    if entry.segments.is_empty() {
+        return Err(
+            "Esta transcripción no tiene segmentos con tiempos y no puede exportarse como subtítulos"
+                .to_string(),
+        );
+    }
+
+    let content = write_subtitles(&entry.segments, format);
+
+    let mut path = std::path::PathBuf::from(&output_path);
+    if path.extension().is_none() {
+        path.set_extension(format.extension());
+    }
+
+    tokio::fs::write(&path, content)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(path.to_string_lossy().to_string())
+}
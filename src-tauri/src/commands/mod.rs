@@ -1,9 +1,17 @@
 pub mod transcription;
 pub mod history;
 pub mod models;
+pub mod queue;
+pub mod server;
+pub mod settings;
+pub mod storage;
 pub mod youtube;
 
 pub use transcription::*;
 pub use history::*;
 pub use models::*;
+pub use queue::*;
+pub use server::*;
+pub use settings::*;
+pub use storage::*;
 pub use youtube::*;
@@ -1,13 +1,25 @@
-use crate::core::{download_model, get_model_path, is_model_downloaded, list_downloaded_models};
+use crate::commands::transcription::AppState;
+use crate::core::{
+    get_model_path, is_model_downloaded, list_all_models, list_downloaded_models, resolve_model,
+    DownloadInfo, ModelVerification, PooledModel,
+};
 use crate::models::WhisperModel;
+use crate::utils::AudioInkError;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, State};
 
 /// Información de un modelo para el frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelInfo {
     pub name: String,
     pub description: String,
+    /// Solo transcribe inglés (variantes `.en`)
+    pub english_only: bool,
+    /// Cuantización de los pesos (`q5_0`, `q5_1`, `q8_0`), si la tiene
+    pub quantization: Option<String>,
+    /// Importado por el usuario con `import_model`
+    pub custom: bool,
     pub size_bytes: u64,
     pub size_formatted: String,
     pub downloaded: bool,
@@ -19,6 +31,9 @@ impl From<&WhisperModel> for ModelInfo {
         Self {
             name: model.to_string(),
             description: model.description().to_string(),
+            english_only: model.is_english_only(),
+            quantization: model.spec().and_then(|s| s.quantization).map(str::to_string),
+            custom: model.is_custom(),
             size_bytes: size,
             size_formatted: format_size(size),
             downloaded: is_model_downloaded(model),
@@ -26,6 +41,29 @@ impl From<&WhisperModel> for ModelInfo {
     }
 }
 
+/// Modelo cargado en memoria
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadedModelInfo {
+    pub name: String,
+    pub size_bytes: u64,
+    pub size_formatted: String,
+    /// Alguna transcripción lo está usando
+    pub in_use: bool,
+    pub last_used: DateTime<Utc>,
+}
+
+impl From<PooledModel> for LoadedModelInfo {
+    fn from(pooled: PooledModel) -> Self {
+        Self {
+            name: pooled.model.to_string(),
+            size_bytes: pooled.size_bytes,
+            size_formatted: format_size(pooled.size_bytes),
+            in_use: pooled.in_use,
+            last_used: pooled.last_used,
+        }
+    }
+}
+
 fn format_size(bytes: u64) -> String {
     const MB: u64 = 1024 * 1024;
     const GB: u64 = MB * 1024;
@@ -40,7 +78,7 @@ fn format_size(bytes: u64) -> String {
 /// Lista todos los modelos disponibles
 #[tauri::command]
 pub fn list_models() -> Vec<ModelInfo> {
-    WhisperModel::all().iter().map(ModelInfo::from).collect()
+    list_all_models().iter().map(ModelInfo::from).collect()
 }
 
 /// Lista solo los modelos descargados
@@ -60,19 +98,35 @@ pub fn check_model_downloaded(model_name: String) -> Result<bool, String> {
 }
 
 /// Descarga un modelo de Whisper
+/// Si ya se está descargando, espera a esa descarga; si hay otra en curso, se pone en cola.
+/// Los eventos llevan el id de la descarga, que sirve para cancelarla
 #[tauri::command]
 pub async fn download_whisper_model(
     app: AppHandle,
+    state: State<'_, AppState>,
     model_name: String,
 ) -> Result<String, String> {
     let model = parse_model_name(&model_name)?;
+    let ticket = state.downloads.enqueue(&model).map_err(|e| e.to_string())?;
+    let download_id = ticket.id.clone();
+
+    let _ = app.emit(
+        "model-download-queued",
+        serde_json::json!({
+            "id": download_id,
+            "model": model.to_string(),
+            "joined": ticket.joined,
+        }),
+    );
 
     // Callback para emitir progreso
     let app_clone = app.clone();
+    let progress_id = download_id.clone();
     let on_progress = Box::new(move |progress: f32, downloaded: u64, total: u64| {
         let _ = app_clone.emit(
             "model-download-progress",
             serde_json::json!({
+                "id": progress_id,
                 "model": model_name.clone(),
                 "progress": progress,
                 "downloaded": downloaded,
@@ -83,14 +137,24 @@ pub async fn download_whisper_model(
         );
     });
 
-    let path = download_model(&model, Some(on_progress))
-        .await
-        .map_err(|e| e.to_string())?;
+    let result = state.downloads.wait(ticket, Some(on_progress)).await;
+    let path = match result {
+        Ok(path) => path,
+        Err(AudioInkError::Cancelled) => {
+            let _ = app.emit(
+                "model-download-cancelled",
+                serde_json::json!({ "id": download_id, "model": model.to_string() }),
+            );
+            return Err(AudioInkError::Cancelled.to_string());
+        }
+        Err(e) => return Err(e.to_string()),
+    };
 
     // Emitir evento de completado
     let _ = app.emit(
         "model-download-complete",
         serde_json::json!({
+            "id": download_id,
             "model": model.to_string(),
             "path": path.to_string_lossy().to_string(),
         }),
@@ -99,15 +163,65 @@ pub async fn download_whisper_model(
     Ok(path.to_string_lossy().to_string())
 }
 
+/// Cancela una descarga en cola o en curso y borra su archivo parcial
+/// Devuelve false si no existe una descarga con ese id
+#[tauri::command]
+pub fn cancel_model_download(
+    state: State<'_, AppState>,
+    download_id: String,
+) -> Result<bool, String> {
+    state.downloads.cancel(&download_id).map_err(|e| e.to_string())
+}
+
+/// Lista las descargas en curso y en cola
+#[tauri::command]
+pub fn get_model_downloads(state: State<'_, AppState>) -> Result<Vec<DownloadInfo>, String> {
+    state.downloads.list().map_err(|e| e.to_string())
+}
+
 /// Elimina un modelo descargado
 #[tauri::command]
-pub async fn delete_whisper_model(model_name: String) -> Result<(), String> {
+pub async fn delete_whisper_model(
+    state: State<'_, AppState>,
+    model_name: String,
+) -> Result<(), String> {
     let model = parse_model_name(&model_name)?;
+    state.service.engines().unload(&model).map_err(|e| e.to_string())?;
     crate::core::delete_model(&model)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Recalcula el SHA-256 de los modelos instalados y lo compara con el esperado
+#[tauri::command]
+pub async fn verify_models() -> Vec<ModelVerification> {
+    crate::core::verify_models().await
+}
+
+/// Importa un modelo ggml propio (p. ej. un fine-tune) y lo deja seleccionable
+#[tauri::command]
+pub async fn import_model(path: String, display_name: String) -> Result<ModelInfo, String> {
+    let custom = crate::core::import_model(std::path::Path::new(&path), &display_name)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(ModelInfo::from(&WhisperModel::Custom(custom)))
+}
+
+/// Lista los modelos cargados en memoria, del más al menos usado recientemente
+#[tauri::command]
+pub fn get_loaded_models(state: State<'_, AppState>) -> Result<Vec<LoadedModelInfo>, String> {
+    let loaded = state.service.engines().loaded().map_err(|e| e.to_string())?;
+    Ok(loaded.into_iter().map(LoadedModelInfo::from).collect())
+}
+
+/// Libera la memoria de un modelo cargado
+/// Devuelve false si no estaba cargado. Las transcripciones en curso con él terminan normalmente
+#[tauri::command]
+pub fn unload_model(state: State<'_, AppState>, model_name: String) -> Result<bool, String> {
+    let model = parse_model_name(&model_name)?;
+    state.service.engines().unload(&model).map_err(|e| e.to_string())
+}
+
 /// Obtiene la ruta de un modelo
 #[tauri::command]
 pub fn get_model_path_cmd(model_name: String) -> Result<String, String> {
@@ -115,15 +229,7 @@ pub fn get_model_path_cmd(model_name: String) -> Result<String, String> {
     Ok(get_model_path(&model).to_string_lossy().to_string())
 }
 
-/// Parsea el nombre del modelo a enum
+/// Parsea el nombre del modelo
 fn parse_model_name(name: &str) -> Result<WhisperModel, String> {
-    match name.to_lowercase().as_str() {
-        "tiny" => Ok(WhisperModel::Tiny),
-        "base" => Ok(WhisperModel::Base),
-        "small" => Ok(WhisperModel::Small),
-        "medium" => Ok(WhisperModel::Medium),
-        "large" => Ok(WhisperModel::Large),
-        "large-v3-turbo" => Ok(WhisperModel::LargeV3Turbo),
-        _ => Err(format!("Modelo desconocido: {}", name)),
-    }
+    resolve_model(name).ok_or_else(|| format!("Modelo desconocido: {}", name))
 }
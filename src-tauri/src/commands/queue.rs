@@ -0,0 +1,153 @@
+use crate::commands::transcription::AppState;
+use crate::models::{JobSource, JobStatus, TranscribeOptions, TranscriptionJob};
+use crate::utils::AudioInkError;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Arranca el worker que procesa la cola de forma secuencial
+///
+/// Restaura la cola guardada y queda a la espera de nuevos jobs. Se llama una
+/// vez al iniciar la aplicación.
+pub fn start_queue_worker(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        if let Err(e) = state.job_queue.restore().await {
+            let _ = app.emit("queue-restore-failed", e.to_string());
+        }
+        emit_queue_updated(&app, &state).await;
+
+        loop {
+            // El token se registra antes de que el job pase a `Running`, así
+            // `remove_job` siempre puede cancelarlo
+            let mut started = None;
+            let next = state
+                .job_queue
+                .start_next(|job| started = Some((job.id.clone(), state.register_job(&job.id))))
+                .await;
+
+            let (job, cancel) = match (next, started) {
+                (Ok(Some(job)), Some((_, Ok(cancel)))) => (job, cancel),
+                (Ok(Some(job)), Some((_, Err(e)))) => {
+                    // Otra transcripción en curso usa el mismo id
+                    let _ = state
+                        .job_queue
+                        .finish(&job.id, JobStatus::Failed, Some(e.to_string()))
+                        .await;
+                    emit_queue_updated(&app, &state).await;
+                    continue;
+                }
+                (next, started) => {
+                    // Sin jobs en espera, o el job volvió a la cola al fallar el guardado
+                    if let Some((job_id, Ok(_))) = started {
+                        state.finish_job(&job_id);
+                    }
+                    if next.is_err() {
+                        emit_queue_updated(&app, &state).await;
+                    }
+                    state.job_queue.wait_for_jobs().await;
+                    continue;
+                }
+            };
+            emit_queue_updated(&app, &state).await;
+
+            let result = state
+                .run_registered_job(
+                    &app,
+                    &job.id,
+                    &cancel,
+                    job.source.clone(),
+                    job.options.clone(),
+                )
+                .await;
+
+            let (status, error) = match result {
+                Ok(_) => (JobStatus::Done, None),
+                Err(AudioInkError::Cancelled) => (JobStatus::Cancelled, None),
+                Err(e) => (JobStatus::Failed, Some(e.to_string())),
+            };
+            let _ = state.job_queue.finish(&job.id, status, error).await;
+            emit_queue_updated(&app, &state).await;
+        }
+    });
+}
+
+/// Emite el estado completo de la cola para la UI
+async fn emit_queue_updated(app: &AppHandle, state: &AppState) {
+    let jobs = state.job_queue.list().await;
+    let _ = app.emit("queue-updated", jobs);
+}
+
+/// Añade archivos o URLs a la cola de transcripción
+/// Cada entrada que empiece por http:// o https:// se trata como URL
+#[tauri::command]
+pub async fn enqueue_transcriptions(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    sources: Vec<String>,
+    options: TranscribeOptions,
+) -> Result<Vec<TranscriptionJob>, String> {
+    let jobs: Vec<TranscriptionJob> = sources
+        .iter()
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| TranscriptionJob::new(JobSource::parse(s), options.clone()))
+        .collect();
+
+    let queued = state
+        .job_queue
+        .enqueue(jobs)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let _ = app.emit("queue-updated", &queued);
+    Ok(queued)
+}
+
+/// Lista los jobs de la cola en orden de procesamiento
+#[tauri::command]
+pub async fn list_jobs(state: State<'_, AppState>) -> Result<Vec<TranscriptionJob>, String> {
+    Ok(state.job_queue.list().await)
+}
+
+/// Mueve un job a otra posición de la cola
+#[tauri::command]
+pub async fn move_job(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    job_id: String,
+    position: usize,
+) -> Result<bool, String> {
+    let moved = state
+        .job_queue
+        .move_job(&job_id, position)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if moved {
+        emit_queue_updated(&app, &state).await;
+    }
+    Ok(moved)
+}
+
+/// Elimina un job de la cola, cancelándolo si está en ejecución
+#[tauri::command]
+pub async fn remove_job(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    job_id: String,
+) -> Result<bool, String> {
+    let removed = state
+        .job_queue
+        .remove(&job_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    match removed {
+        Some(job) => {
+            if job.status == JobStatus::Running {
+                state.cancel_job(&job_id);
+            }
+            emit_queue_updated(&app, &state).await;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
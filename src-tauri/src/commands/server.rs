@@ -0,0 +1,55 @@
+use crate::commands::transcription::AppState;
+use crate::server::{ApiServer, ServerConfig};
+use serde::Serialize;
+use tauri::State;
+
+/// Estado del servidor HTTP compatible con OpenAI
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiServerStatus {
+    pub running: bool,
+    /// Dirección en la que escucha, p. ej. "127.0.0.1:8178"
+    pub address: Option<String>,
+}
+
+/// Arranca el servidor HTTP local
+/// Escucha solo en localhost salvo que `config.host` indique otra dirección
+#[tauri::command]
+pub async fn start_api_server(
+    state: State<'_, AppState>,
+    config: Option<ServerConfig>,
+) -> Result<ApiServerStatus, String> {
+    let mut server = state.api_server.lock().await;
+
+    if server.is_none() {
+        let config = config.unwrap_or_default();
+        let started = ApiServer::start(config, state.service.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        *server = Some(started);
+    }
+
+    Ok(status_of(server.as_ref()))
+}
+
+/// Detiene el servidor HTTP local
+#[tauri::command]
+pub async fn stop_api_server(state: State<'_, AppState>) -> Result<ApiServerStatus, String> {
+    let server = state.api_server.lock().await.take();
+    if let Some(server) = server {
+        server.stop().await;
+    }
+    Ok(status_of(None))
+}
+
+/// Obtiene el estado del servidor HTTP local
+#[tauri::command]
+pub async fn get_api_server_status(state: State<'_, AppState>) -> Result<ApiServerStatus, String> {
+    Ok(status_of(state.api_server.lock().await.as_ref()))
+}
+
+fn status_of(server: Option<&ApiServer>) -> ApiServerStatus {
+    ApiServerStatus {
+        running: server.is_some(),
+        address: server.map(|s| s.local_addr().to_string()),
+    }
+}
@@ -0,0 +1,22 @@
+use crate::commands::transcription::AppState;
+use crate::core::{set_model_sources, ModelSources};
+use crate::models::AppSettings;
+use tauri::State;
+
+/// Obtiene los ajustes de la aplicación
+#[tauri::command]
+pub fn get_settings(state: State<'_, AppState>) -> AppSettings {
+    state.settings.get()
+}
+
+/// Guarda los ajustes de la aplicación
+/// Devuelve los valores aplicados (los fuera de rango se ajustan)
+#[tauri::command]
+pub async fn update_settings(
+    state: State<'_, AppState>,
+    settings: AppSettings,
+) -> Result<AppSettings, String> {
+    let settings = state.settings.update(settings).await.map_err(|e| e.to_string())?;
+    set_model_sources(ModelSources::from(&settings));
+    Ok(settings)
+}
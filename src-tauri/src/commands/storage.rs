@@ -0,0 +1,61 @@
+use crate::commands::transcription::AppState;
+use crate::core::{
+    available_space, dir_size, get_models_storage_info, partial_downloads_size, temp_files_size,
+};
+use serde::Serialize;
+use tauri::State;
+
+/// Espacio usado por un modelo instalado
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelStorage {
+    pub name: String,
+    pub size_bytes: u64,
+}
+
+/// Uso de disco de la aplicación
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageInfo {
+    pub models_dir: String,
+    pub models: Vec<ModelStorage>,
+    pub models_total_bytes: u64,
+    pub models_total_formatted: String,
+    /// Descargas de modelos sin terminar (se reanudan en el próximo intento)
+    pub partial_downloads_bytes: u64,
+    /// Temporales del pipeline: audio acelerado, extraído de vídeo o descargado de YouTube
+    pub temp_files_bytes: u64,
+    /// Historial y transcripciones guardadas
+    pub history_bytes: u64,
+    /// Espacio libre en el disco de los modelos, si se puede consultar
+    pub free_space_bytes: Option<u64>,
+}
+
+/// Obtiene el uso de disco de modelos, temporales e historial
+#[tauri::command]
+pub async fn get_storage_info(state: State<'_, AppState>) -> Result<StorageInfo, String> {
+    let models = get_models_storage_info().await.map_err(|e| e.to_string())?;
+
+    let history = &state.history_manager;
+    let history_index = tokio::fs::metadata(history.history_file())
+        .await
+        .map(|metadata| metadata.len())
+        .unwrap_or(0);
+    let history_bytes = history_index + dir_size(history.transcriptions_dir()).await;
+
+    Ok(StorageInfo {
+        models_dir: models.models_dir.to_string_lossy().to_string(),
+        models_total_bytes: models.total_size,
+        models_total_formatted: models.total_size_formatted(),
+        free_space_bytes: available_space(&models.models_dir).ok(),
+        models: models
+            .model_sizes
+            .into_iter()
+            .map(|(model, size_bytes)| ModelStorage {
+                name: model.to_string(),
+                size_bytes,
+            })
+            .collect(),
+        partial_downloads_bytes: partial_downloads_size().await,
+        temp_files_bytes: temp_files_size().await,
+        history_bytes,
+    })
+}
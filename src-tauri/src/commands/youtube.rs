@@ -1,4 +1,4 @@
-use crate::models::{SourceType, TranscriptionEntry, TranscriptionResult};
+use crate::models::{SourceType, Task, TranscriptionEntry, TranscriptionResult};
 use crate::commands::AppState;
 use serde::{Deserialize, Serialize};
 use tauri::State;
@@ -90,6 +90,11 @@ pub async fn get_youtube_captions(
         language: Some(lang_code.to_string()),
         audio_info: None,
         processing_time,
+        segments: Vec::new(),
+        task: Task::Transcribe,
+        language_probabilities: Vec::new(),
+        language_spans: Vec::new(),
+        hallucinations: None,
     };
 
     // Save to history
@@ -136,13 +136,118 @@ fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
         .collect()
 }
 
+/// Parámetros de la división en chunks
+#[derive(Debug, Clone)]
+pub struct ChunkConfig {
+    /// Duración nominal de cada chunk
+    pub chunk_secs: f32,
+    /// Distancia máxima al corte nominal en la que se busca un silencio
+    pub search_window_secs: f32,
+    /// Audio del chunk anterior que se repite al inicio de cada chunk
+    pub overlap_secs: f32,
+}
+
+impl Default for ChunkConfig {
+    fn default() -> Self {
+        Self {
+            chunk_secs: CHUNK_DURATION_SECS,
+            search_window_secs: 5.0,
+            overlap_secs: 0.0,
+        }
+    }
+}
+
+/// Chunk de audio con su posición exacta en el audio completo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioChunk<'a> {
+    /// Primer sample del chunk en el audio completo
+    pub offset_samples: usize,
+    /// Samples iniciales que repiten el final del chunk anterior
+    pub overlap_samples: usize,
+    pub samples: &'a [f32],
+}
+
+impl AudioChunk<'_> {
+    /// Inicio del chunk en milisegundos
+    pub fn offset_ms(&self) -> i64 {
+        samples_to_ms(self.offset_samples)
+    }
+
+    /// Fin de la zona solapada (inicio del audio nuevo) en milisegundos
+    pub fn new_audio_start_ms(&self) -> i64 {
+        samples_to_ms(self.offset_samples + self.overlap_samples)
+    }
+}
+
+fn samples_to_ms(samples: usize) -> i64 {
+    (samples as u64 * 1000 / WHISPER_SAMPLE_RATE as u64) as i64
+}
+
 /// Divide el audio en chunks para procesamiento de archivos grandes
-pub fn split_into_chunks(samples: &[f32]) -> Vec<Vec<f32>> {
-    let chunk_size = (CHUNK_DURATION_SECS * WHISPER_SAMPLE_RATE as f32) as usize;
-    samples
-        .chunks(chunk_size)
-        .map(|chunk| chunk.to_vec())
-        .collect()
+pub fn split_into_chunks(samples: &[f32]) -> Vec<AudioChunk<'_>> {
+    split_into_chunks_with(samples, &ChunkConfig::default())
+}
+
+/// Divide el audio en chunks cortando en el punto de menor energía cerca de cada límite
+///
+/// A igual energía se prefiere el punto más cercano al corte nominal, de modo
+/// que el audio sin variaciones se divide en chunks de duración exacta.
+pub fn split_into_chunks_with<'a>(samples: &'a [f32], config: &ChunkConfig) -> Vec<AudioChunk<'a>> {
+    let rate = WHISPER_SAMPLE_RATE as f32;
+    let chunk_len = ((config.chunk_secs * rate) as usize).max(1);
+    let window = (config.search_window_secs.max(0.0) * rate) as usize;
+    let overlap = ((config.overlap_secs.max(0.0) * rate) as usize).min(chunk_len / 2);
+    let frame_len = (rate * 0.02) as usize; // 20ms
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < samples.len() {
+        let nominal = start + chunk_len;
+        let end = if nominal >= samples.len() {
+            samples.len()
+        } else {
+            let lo = nominal.saturating_sub(window).max(start + frame_len);
+            let hi = (nominal + window).min(samples.len() - frame_len);
+            quietest_point(samples, lo, hi, nominal, frame_len)
+        };
+
+        let chunk_start = if chunks.is_empty() { start } else { start.saturating_sub(overlap) };
+        chunks.push(AudioChunk {
+            offset_samples: chunk_start,
+            overlap_samples: start - chunk_start,
+            samples: &samples[chunk_start..end],
+        });
+        start = end;
+    }
+
+    chunks
+}
+
+/// Busca en `[lo, hi]` el inicio del frame con menor energía, desempatando por cercanía a `nominal`
+fn quietest_point(samples: &[f32], lo: usize, hi: usize, nominal: usize, frame_len: usize) -> usize {
+    if lo >= hi {
+        return nominal;
+    }
+
+    let mut best = nominal;
+    let mut best_energy = f32::INFINITY;
+
+    // Frames alineados con el corte nominal para que este sea un candidato
+    let first = nominal - (nominal - lo) / frame_len * frame_len;
+    let mut pos = first;
+    while pos <= hi {
+        let frame = &samples[pos..pos + frame_len];
+        let energy = frame.iter().map(|s| s * s).sum::<f32>();
+        let closer = pos.abs_diff(nominal) < best.abs_diff(nominal);
+        if energy < best_energy || (energy == best_energy && closer) {
+            best = pos;
+            best_energy = energy;
+        }
+        pos += frame_len;
+    }
+
+    best
 }
 
 /// Calcula la duración del audio en segundos
@@ -156,6 +261,158 @@ pub fn needs_chunking(samples: &[f32]) -> bool {
     duration > 120.0 // > 2 minutos
 }
 
+/// Parámetros de la detección de voz por energía
+#[derive(Debug, Clone)]
+pub struct VadConfig {
+    /// Duración de cada frame de análisis
+    pub frame_ms: u32,
+    /// Energía RMS mínima para considerar un frame como voz
+    pub min_rms: f32,
+    /// Factor sobre el ruido de fondo (percentil 10 de la energía) para considerar voz
+    pub noise_ratio: f32,
+    /// Regiones de voz más cortas se descartan
+    pub min_speech_ms: u32,
+    /// Silencios más cortos no separan regiones
+    pub min_silence_ms: u32,
+    /// Margen añadido a cada lado de una región
+    pub padding_ms: u32,
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self {
+            frame_ms: 30,
+            min_rms: 0.01,
+            noise_ratio: 3.0,
+            min_speech_ms: 250,
+            min_silence_ms: 600,
+            padding_ms: 200,
+        }
+    }
+}
+
+/// Región de voz en samples, `[start, end)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpeechRegion {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Energía RMS de cada frame de `frame_len` samples
+pub fn frame_rms(samples: &[f32], frame_len: usize) -> Vec<f32> {
+    samples
+        .chunks(frame_len.max(1))
+        .map(|frame| (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt())
+        .collect()
+}
+
+fn ms_to_samples(ms: u32) -> usize {
+    (ms as usize * WHISPER_SAMPLE_RATE as usize) / 1000
+}
+
+/// Detecta las regiones con voz a partir de la energía de cada frame
+pub fn detect_speech_regions(samples: &[f32], config: &VadConfig) -> Vec<SpeechRegion> {
+    let frame_len = ms_to_samples(config.frame_ms).max(1);
+    let energies = frame_rms(samples, frame_len);
+    if energies.is_empty() {
+        return Vec::new();
+    }
+
+    // Umbral adaptativo: por encima del ruido de fondo y de un mínimo absoluto
+    let mut sorted = energies.clone();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let noise_floor = sorted[sorted.len() / 10];
+    let threshold = (noise_floor * config.noise_ratio).max(config.min_rms);
+
+    // Frames consecutivos con voz, uniendo los separados por silencios cortos
+    let max_gap_frames = (config.min_silence_ms / config.frame_ms.max(1)) as usize;
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for (i, _) in energies.iter().enumerate().filter(|(_, e)| **e >= threshold) {
+        match runs.last_mut() {
+            Some((_, end)) if i - *end <= max_gap_frames => *end = i + 1,
+            _ => runs.push((i, i + 1)),
+        }
+    }
+
+    let min_speech = ms_to_samples(config.min_speech_ms);
+    let padding = ms_to_samples(config.padding_ms);
+    let mut regions: Vec<SpeechRegion> = Vec::new();
+
+    for (start_frame, end_frame) in runs {
+        let start = start_frame * frame_len;
+        let end = (end_frame * frame_len).min(samples.len());
+        if end - start < min_speech {
+            continue;
+        }
+
+        let region = SpeechRegion {
+            start: start.saturating_sub(padding),
+            end: (end + padding).min(samples.len()),
+        };
+
+        // El margen puede solapar la región anterior
+        match regions.last_mut() {
+            Some(last) if region.start <= last.end => last.end = region.end,
+            _ => regions.push(region),
+        }
+    }
+
+    regions
+}
+
+/// Correspondencia entre la línea de tiempo compactada y la original
+#[derive(Debug, Clone, Default)]
+pub struct TimelineMap {
+    /// (inicio en el audio compactado, inicio en el original, longitud) en samples
+    spans: Vec<(usize, usize, usize)>,
+}
+
+impl TimelineMap {
+    /// Convierte un tiempo del audio compactado al audio original
+    pub fn to_original_ms(&self, compact_ms: i64) -> i64 {
+        let rate = WHISPER_SAMPLE_RATE as i64;
+        let compact = (compact_ms.max(0) * rate / 1000) as usize;
+
+        let span = self
+            .spans
+            .iter()
+            .rev()
+            .find(|(compact_start, _, _)| *compact_start <= compact)
+            .or(self.spans.first());
+
+        match span {
+            Some(&(compact_start, original_start, len)) => {
+                let original = original_start + compact.saturating_sub(compact_start).min(len);
+                original as i64 * 1000 / rate
+            }
+            None => compact_ms,
+        }
+    }
+}
+
+/// Audio con solo las regiones de voz
+#[derive(Debug, Clone)]
+pub struct CompactedAudio {
+    pub samples: Vec<f32>,
+    pub timeline: TimelineMap,
+}
+
+/// Concatena las regiones de voz en un único buffer
+pub fn compact_speech(samples: &[f32], regions: &[SpeechRegion]) -> CompactedAudio {
+    let mut compacted = Vec::with_capacity(regions.iter().map(|r| r.end - r.start).sum());
+    let mut spans = Vec::with_capacity(regions.len());
+
+    for region in regions {
+        spans.push((compacted.len(), region.start, region.end - region.start));
+        compacted.extend_from_slice(&samples[region.start..region.end]);
+    }
+
+    CompactedAudio {
+        samples: compacted,
+        timeline: TimelineMap { spans },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -189,6 +446,41 @@ mod tests {
 
         // Debería tener 3 chunks (60 segundos cada uno)
         assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[1].offset_samples, 60 * 16000);
+        assert!(chunks.iter().all(|c| c.samples.len() == 60 * 16000));
+    }
+
+    #[test]
+    fn test_split_into_chunks_cuts_at_silence() {
+        // Tono continuo con un silencio breve a los 57.5 segundos
+        let mut samples: Vec<f32> = (0..150 * 16000).map(|i| (i as f32 * 0.05).sin() * 0.3).collect();
+        let gap = 57 * 16000 + 8000;
+        samples[gap..gap + 1600].fill(0.0);
+
+        let chunks = split_into_chunks(&samples);
+        let cut = chunks[1].offset_samples;
+        assert!(cut >= gap && cut + 320 <= gap + 1600, "cut at {}", cut);
+
+        // Los chunks cubren todo el audio sin huecos
+        assert_eq!(chunks[0].samples.len(), cut);
+        let covered: usize = chunks.iter().map(|c| c.samples.len()).sum();
+        assert_eq!(covered, samples.len());
+    }
+
+    #[test]
+    fn test_split_into_chunks_with_overlap() {
+        let samples: Vec<f32> = vec![0.0; 150 * 16000];
+        let config = ChunkConfig {
+            overlap_secs: 2.0,
+            ..ChunkConfig::default()
+        };
+        let chunks = split_into_chunks_with(&samples, &config);
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].overlap_samples, 0);
+        assert_eq!(chunks[1].offset_ms(), 58_000);
+        assert_eq!(chunks[1].new_audio_start_ms(), 60_000);
+        assert_eq!(chunks[2].offset_samples + chunks[2].samples.len(), samples.len());
     }
 
     #[test]
@@ -201,4 +493,52 @@ mod tests {
         let long_audio: Vec<f32> = vec![0.0; 180 * 16000];
         assert!(needs_chunking(&long_audio));
     }
+
+    /// Silencio con ruido leve y dos tramos de tono
+    fn speech_fixture() -> Vec<f32> {
+        let rate = 16000;
+        let tone = |n: usize| (0..n).map(|i| (i as f32 * 0.05).sin() * 0.3);
+        let noise = |n: usize| (0..n).map(|i| if i % 2 == 0 { 0.001 } else { -0.001 });
+
+        let mut samples: Vec<f32> = noise(2 * rate).collect();
+        samples.extend(tone(rate));
+        samples.extend(noise(3 * rate));
+        samples.extend(tone(rate));
+        samples.extend(noise(rate));
+        samples
+    }
+
+    #[test]
+    fn test_detect_speech_regions() {
+        let samples = speech_fixture();
+        let config = VadConfig::default();
+        let regions = detect_speech_regions(&samples, &config);
+
+        assert_eq!(regions.len(), 2);
+        let padding = 200 * 16;
+        assert!(regions[0].start.abs_diff(2 * 16000 - padding) <= 480);
+        assert!(regions[1].end.abs_diff(7 * 16000 + padding) <= 480);
+
+        // Solo ruido: ninguna región
+        let silence = vec![0.001; 5 * 16000];
+        assert!(detect_speech_regions(&silence, &config).is_empty());
+    }
+
+    #[test]
+    fn test_compacted_timeline_maps_back() {
+        let samples = speech_fixture();
+        let regions = vec![
+            SpeechRegion { start: 2 * 16000, end: 3 * 16000 },
+            SpeechRegion { start: 6 * 16000, end: 7 * 16000 },
+        ];
+        let compacted = compact_speech(&samples, &regions);
+
+        assert_eq!(compacted.samples.len(), 2 * 16000);
+        assert_eq!(compacted.timeline.to_original_ms(0), 2000);
+        assert_eq!(compacted.timeline.to_original_ms(500), 2500);
+        assert_eq!(compacted.timeline.to_original_ms(1000), 6000);
+        assert_eq!(compacted.timeline.to_original_ms(1750), 6750);
+        // Más allá del final se queda en el final de la última región
+        assert_eq!(compacted.timeline.to_original_ms(5000), 7000);
+    }
 }
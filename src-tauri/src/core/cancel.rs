@@ -0,0 +1,166 @@
+//! Cancelación de trabajos largos
+//!
+//! Un `CancellationToken` se comparte entre el trabajo y quien pueda cancelarlo.
+//! Se comprueba entre chunks de transcripción, desde el callback de abort de
+//! Whisper y mientras se espera a procesos externos (ffmpeg, yt-dlp), que se
+//! matan en cuanto se cancela el token.
+
+use crate::utils::{AudioInkError, AudioInkResult};
+use std::io::Read;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Cada cuánto se consulta si un proceso hijo (o una espera async) terminó o se canceló
+const CHILD_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Flag de cancelación compartido
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Solicita la cancelación
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Indica si se solicitó la cancelación
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Devuelve `AudioInkError::Cancelled` si se solicitó la cancelación
+    pub fn check(&self) -> AudioInkResult<()> {
+        if self.is_cancelled() {
+            Err(AudioInkError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Se resuelve cuando se solicita la cancelación, para usar en `tokio::select!`
+    pub async fn cancelled(&self) {
+        while !self.is_cancelled() {
+            tokio::time::sleep(CHILD_POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Ejecuta un comando hasta que termine, matándolo si se cancela el token
+///
+/// Se comporta como `Command::output()`: captura stdout y stderr. `program`
+/// solo se usa para identificar los errores al lanzarlo.
+pub fn run_command_cancellable(
+    command: &mut Command,
+    program: &str,
+    cancel: &CancellationToken,
+) -> AudioInkResult<Output> {
+    cancel.check()?;
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AudioInkError::Internal(format!("Failed to run {}: {}", program, e)))?;
+
+    // Vaciar los pipes en hilos aparte para que un proceso que escribe mucho
+    // (ffmpeg escribe el progreso en stderr) no se bloquee con el pipe lleno
+    let stdout_reader = child.stdout.take().map(spawn_pipe_reader);
+    let stderr_reader = child.stderr.take().map(spawn_pipe_reader);
+
+    let status = loop {
+        if cancel.is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(AudioInkError::Cancelled);
+        }
+
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => std::thread::sleep(CHILD_POLL_INTERVAL),
+            Err(e) => {
+                let _ = child.kill();
+                return Err(AudioInkError::Internal(format!(
+                    "Failed to wait for {}: {}",
+                    program, e
+                )));
+            }
+        }
+    };
+
+    let join = |reader: Option<std::thread::JoinHandle<Vec<u8>>>| {
+        reader
+            .map(|handle| handle.join().unwrap_or_default())
+            .unwrap_or_default()
+    };
+
+    Ok(Output {
+        status,
+        stdout: join(stdout_reader),
+        stderr: join(stderr_reader),
+    })
+}
+
+fn spawn_pipe_reader<R: Read + Send + 'static>(mut pipe: R) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = pipe.read_to_end(&mut buffer);
+        buffer
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_check() {
+        let token = CancellationToken::new();
+        let shared = token.clone();
+        assert!(token.check().is_ok());
+
+        shared.cancel();
+        assert!(token.is_cancelled());
+        assert!(matches!(token.check(), Err(AudioInkError::Cancelled)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command_captures_output() {
+        let output = run_command_cancellable(
+            Command::new("sh").args(["-c", "echo out; echo err >&2"]),
+            "sh",
+            &CancellationToken::new(),
+        )
+        .unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "out");
+        assert_eq!(String::from_utf8_lossy(&output.stderr).trim(), "err");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command_kills_on_cancel() {
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            canceller.cancel();
+        });
+
+        let started = std::time::Instant::now();
+        let result = run_command_cancellable(Command::new("sleep").arg("10"), "sleep", &token);
+
+        assert!(matches!(result, Err(AudioInkError::Cancelled)));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,324 @@
+//! Verificación de integridad de los modelos (SHA-256)
+//!
+//! El hash esperado de un modelo del catálogo es el fijado en `MODEL_CATALOG`.
+//! Si la entrada aún no lo tiene y se descarga de Hugging Face, se pide allí
+//! (publica el SHA-256 de los archivos LFS en `X-Linked-Etag`) y se anota en
+//! `checksums.json`; con un mirror o un origen `file://` no se consulta. El hash de un archivo recién
+//! descargado nunca se toma como esperado: sin un hash conocido el modelo
+//! queda `Unverified`.
+
+use crate::core::{get_model_path, get_models_dir, list_downloaded_models, CancellationToken};
+use crate::models::WhisperModel;
+use crate::utils::{AudioInkError, AudioInkResult};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::fs;
+use tokio::sync::Mutex;
+
+/// Hashes conocidos por nombre de archivo, dentro del directorio de modelos
+const CHECKSUMS_FILE: &str = "checksums.json";
+
+/// Tiempo máximo para obtener el hash publicado
+const PUBLISHED_HASH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Serializa las escrituras de `checksums.json`
+static CHECKSUMS_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Resultado de verificar un modelo instalado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelIntegrity {
+    /// El hash coincide con el esperado
+    Verified,
+    /// El hash no coincide: el archivo está truncado o corrupto
+    Corrupted,
+    /// No hay hash conocido con el que comparar
+    Unverified,
+}
+
+/// Verificación de un modelo para el frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelVerification {
+    pub model: String,
+    pub status: ModelIntegrity,
+    /// Detalle del error si está corrupto o no se pudo leer
+    pub error: Option<String>,
+}
+
+/// SHA-256 de un archivo en hexadecimal
+pub async fn sha256_file(path: &Path) -> AudioInkResult<String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut file = std::fs::File::open(&path)?;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut file, &mut hasher)?;
+        Ok(format!("{:x}", hasher.finalize()))
+    })
+    .await
+    .map_err(|e| AudioInkError::Internal(format!("Error de task: {}", e)))?
+}
+
+/// Hash esperado de un modelo, si se conoce
+pub fn expected_checksum(model: &WhisperModel) -> Option<String> {
+    expected_checksum_in(&get_models_dir(), model)
+}
+
+/// El hash fijado en el catálogo o, si no lo hay, el anotado en `models_dir`
+fn expected_checksum_in(models_dir: &Path, model: &WhisperModel) -> Option<String> {
+    model
+        .sha256()
+        .map(str::to_ascii_lowercase)
+        .or_else(|| read_checksums(models_dir).remove(model.filename()))
+}
+
+/// Anota el hash esperado de un archivo de modelo
+pub async fn record_checksum(filename: &str, sha256: &str) -> AudioInkResult<()> {
+    update_checksums(&get_models_dir(), |checksums| {
+        checksums.insert(filename.to_string(), sha256.to_string());
+    })
+    .await
+}
+
+/// Olvida el hash de un archivo de modelo eliminado
+pub async fn forget_checksum(filename: &str) -> AudioInkResult<()> {
+    update_checksums(&get_models_dir(), |checksums| {
+        checksums.remove(filename);
+    })
+    .await
+}
+
+/// Comprueba `path` contra el hash esperado
+/// Devuelve el hash calculado, o `ModelCorrupted` si no coincide
+pub async fn check_file_sha256(
+    path: &Path,
+    expected: Option<&str>,
+    model: &WhisperModel,
+) -> AudioInkResult<String> {
+    let actual = sha256_file(path).await?;
+    match expected {
+        Some(expected) if !expected.eq_ignore_ascii_case(&actual) => {
+            Err(AudioInkError::ModelCorrupted(format!(
+                "{} (SHA-256 esperado {}, obtenido {})",
+                model, expected, actual
+            )))
+        }
+        _ => Ok(actual),
+    }
+}
+
+/// Vuelve a calcular el hash de un modelo instalado
+pub async fn verify_model(model: &WhisperModel) -> AudioInkResult<ModelIntegrity> {
+    verify_model_at(&get_models_dir(), &get_model_path(model), model).await
+}
+
+/// Verifica todos los modelos instalados
+pub async fn verify_models() -> Vec<ModelVerification> {
+    let mut results = Vec::new();
+    for model in list_downloaded_models() {
+        let (status, error) = match verify_model(&model).await {
+            Ok(status) => (status, None),
+            Err(e @ AudioInkError::ModelCorrupted(_)) => {
+                (ModelIntegrity::Corrupted, Some(e.to_string()))
+            }
+            Err(e) => (ModelIntegrity::Unverified, Some(e.to_string())),
+        };
+        results.push(ModelVerification {
+            model: model.to_string(),
+            status,
+            error,
+        });
+    }
+    results
+}
+
+/// Hash publicado por Hugging Face para `url`
+///
+/// `/resolve/` responde con una redirección al CDN que lleva el SHA-256 del
+/// archivo LFS en `X-Linked-Etag`, así que se lee sin seguir la redirección.
+/// Devuelve None si no hay respuesta a tiempo o no trae un SHA-256.
+pub async fn fetch_published_sha256(
+    url: &str,
+    cancel: &CancellationToken,
+) -> AudioInkResult<Option<String>> {
+    let Ok(client) = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(PUBLISHED_HASH_TIMEOUT)
+        .build()
+    else {
+        return Ok(None);
+    };
+
+    let response = tokio::select! {
+        response = client.head(url).send() => response,
+        _ = cancel.cancelled() => return Err(AudioInkError::Cancelled),
+    };
+    let Ok(response) = response else {
+        return Ok(None);
+    };
+
+    let headers = response.headers();
+    Ok(headers
+        .get("x-linked-etag")
+        .or_else(|| headers.get(reqwest::header::ETAG))
+        .and_then(|value| value.to_str().ok())
+        .and_then(sha256_from_etag))
+}
+
+/// Extrae un SHA-256 de un ETag (`"abc…"` o `W/"abc…"`); otros ETag se ignoran
+fn sha256_from_etag(etag: &str) -> Option<String> {
+    let value = etag.trim().trim_start_matches("W/").trim_matches('"');
+    let is_sha256 = value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit());
+    is_sha256.then(|| value.to_ascii_lowercase())
+}
+
+/// Verifica el archivo en `path` con los hashes de `models_dir`
+async fn verify_model_at(
+    models_dir: &Path,
+    path: &Path,
+    model: &WhisperModel,
+) -> AudioInkResult<ModelIntegrity> {
+    if !path.exists() {
+        return Err(AudioInkError::ModelNotFound(model.to_string()));
+    }
+
+    let Some(expected) = expected_checksum_in(models_dir, model) else {
+        return Ok(ModelIntegrity::Unverified);
+    };
+    check_file_sha256(path, Some(&expected), model).await?;
+    Ok(ModelIntegrity::Verified)
+}
+
+fn checksums_path(models_dir: &Path) -> PathBuf {
+    models_dir.join(CHECKSUMS_FILE)
+}
+
+fn read_checksums(models_dir: &Path) -> BTreeMap<String, String> {
+    std::fs::read_to_string(checksums_path(models_dir))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+async fn update_checksums(
+    models_dir: &Path,
+    update: impl FnOnce(&mut BTreeMap<String, String>),
+) -> AudioInkResult<()> {
+    let _guard = CHECKSUMS_LOCK.lock().await;
+    let mut checksums = read_checksums(models_dir);
+    update(&mut checksums);
+
+    fs::create_dir_all(models_dir).await?;
+    let path = checksums_path(models_dir);
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, serde_json::to_string_pretty(&checksums)?).await?;
+    fs::rename(&temp_path, &path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ModelSpec;
+
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    #[tokio::test]
+    async fn test_sha256_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("abc.bin");
+        fs::write(&path, b"abc").await.unwrap();
+        assert_eq!(sha256_file(&path).await.unwrap(), ABC_SHA256);
+    }
+
+    #[test]
+    fn test_sha256_from_etag() {
+        let quoted = format!("\"{}\"", ABC_SHA256.to_uppercase());
+        assert_eq!(sha256_from_etag(&quoted).as_deref(), Some(ABC_SHA256));
+        assert_eq!(sha256_from_etag(&format!("W/{}", quoted)).as_deref(), Some(ABC_SHA256));
+        // Los ETag de git (SHA-1) no sirven
+        assert_eq!(sha256_from_etag("\"a9993e364706816aba3e25717850c26c9cd0d89d\""), None);
+    }
+
+    #[tokio::test]
+    async fn test_verify_detects_corruption() {
+        let dir = tempfile::tempdir().unwrap();
+        let model = WhisperModel::TINY;
+        let path = dir.path().join(model.filename());
+        fs::write(&path, b"abc").await.unwrap();
+
+        assert_eq!(
+            verify_model_at(dir.path(), &path, &model).await.unwrap(),
+            ModelIntegrity::Unverified
+        );
+
+        update_checksums(dir.path(), |c| {
+            c.insert(model.filename().to_string(), ABC_SHA256.to_string());
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            verify_model_at(dir.path(), &path, &model).await.unwrap(),
+            ModelIntegrity::Verified
+        );
+
+        // Archivo truncado
+        fs::write(&path, b"ab").await.unwrap();
+        let result = verify_model_at(dir.path(), &path, &model).await;
+        assert!(matches!(result, Err(AudioInkError::ModelCorrupted(_))));
+    }
+
+    #[tokio::test]
+    async fn test_catalog_hash_takes_precedence() {
+        static PINNED: ModelSpec = ModelSpec {
+            name: "pinned",
+            filename: "ggml-pinned.bin",
+            size_bytes: 3,
+            description: "",
+            english_only: false,
+            quantization: None,
+            sha256: Some(ABC_SHA256),
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let model = WhisperModel::Catalog(&PINNED);
+        let path = dir.path().join(model.filename());
+        fs::write(&path, b"abc").await.unwrap();
+
+        // Un hash anotado distinto (p. ej. de un mirror) no cuenta
+        update_checksums(dir.path(), |c| {
+            c.insert(model.filename().to_string(), "0".repeat(64));
+        })
+        .await
+        .unwrap();
+        assert_eq!(expected_checksum_in(dir.path(), &model).as_deref(), Some(ABC_SHA256));
+        assert_eq!(
+            verify_model_at(dir.path(), &path, &model).await.unwrap(),
+            ModelIntegrity::Verified
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_published_sha256_stops_on_cancel() {
+        // Un servidor que acepta la conexión y nunca responde
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/ggml-tiny.bin", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let _connection = listener.accept().await;
+            std::future::pending::<()>().await;
+        });
+
+        let cancel = CancellationToken::new();
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            canceller.cancel();
+        });
+
+        let started = std::time::Instant::now();
+        let result = fetch_published_sha256(&url, &cancel).await;
+        assert!(matches!(result, Err(AudioInkError::Cancelled)));
+        assert!(started.elapsed() < PUBLISHED_HASH_TIMEOUT);
+    }
+}
@@ -0,0 +1,263 @@
+//! Modelos importados por el usuario
+//!
+//! El archivo se enlaza (o se copia, si no se puede enlazar) al directorio de
+//! modelos y se registra en `custom_models.json`. A partir de ahí se
+//! selecciona por su nombre como cualquier modelo del catálogo.
+
+use crate::core::{get_models_dir, WhisperEngine};
+use crate::models::{CustomModel, WhisperModel};
+use crate::utils::{AudioInkError, AudioInkResult};
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tokio::io::AsyncReadExt;
+use tokio::sync::Mutex;
+
+/// Manifiesto de modelos importados, dentro del directorio de modelos
+const MANIFEST_FILE: &str = "custom_models.json";
+
+/// Cabeceras aceptadas: ggml ("ggml" como u32 little-endian) y GGUF
+const MODEL_MAGICS: &[&[u8; 4]] = &[b"lmgg", b"GGUF"];
+
+/// Serializa las escrituras del manifiesto
+static MANIFEST_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Modelos importados registrados
+pub fn list_custom_models() -> Vec<CustomModel> {
+    read_manifest(&get_models_dir())
+}
+
+/// Busca un modelo importado por nombre
+pub fn find_custom_model(name: &str) -> Option<CustomModel> {
+    let name = name.trim().to_lowercase();
+    list_custom_models().into_iter().find(|m| m.name == name)
+}
+
+/// Importa un modelo ggml de Whisper con el nombre `display_name`
+///
+/// Comprueba la cabecera del archivo y que whisper.cpp puede cargarlo antes
+/// de registrarlo.
+pub async fn import_model(source: &Path, display_name: &str) -> AudioInkResult<CustomModel> {
+    import_model_into(&get_models_dir(), source, display_name, |path| {
+        WhisperEngine::validate_model_file(&path)
+    })
+    .await
+}
+
+/// Quita un modelo importado del manifiesto y borra su archivo
+/// Devuelve false si no estaba registrado
+pub async fn remove_custom_model(name: &str) -> AudioInkResult<bool> {
+    let models_dir = get_models_dir();
+    let _guard = MANIFEST_LOCK.lock().await;
+
+    let mut models = read_manifest(&models_dir);
+    let Some(index) = models.iter().position(|m| m.name == name) else {
+        return Ok(false);
+    };
+    let removed = models.remove(index);
+    write_manifest(&models_dir, &models).await?;
+
+    let path = models_dir.join(&removed.filename);
+    if path.exists() {
+        fs::remove_file(&path)
+            .await
+            .map_err(|e| AudioInkError::FileError(e.to_string()))?;
+    }
+    Ok(true)
+}
+
+async fn import_model_into(
+    models_dir: &Path,
+    source: &Path,
+    display_name: &str,
+    validate: impl FnOnce(PathBuf) -> AudioInkResult<()> + Send + 'static,
+) -> AudioInkResult<CustomModel> {
+    let display_name = display_name.trim();
+    let name = model_name_from(display_name);
+    if name.is_empty() {
+        return Err(AudioInkError::FileError(
+            "El nombre del modelo no puede estar vacío".to_string(),
+        ));
+    }
+
+    // Bloqueado desde la comprobación del nombre hasta escribir el manifiesto:
+    // dos importaciones con el mismo nombre no pueden pisarse el archivo
+    let _guard = MANIFEST_LOCK.lock().await;
+    ensure_name_available(models_dir, &name)?;
+    check_model_header(source).await?;
+
+    fs::create_dir_all(models_dir)
+        .await
+        .map_err(|e| AudioInkError::FileError(e.to_string()))?;
+    let filename = format!("custom-{}.bin", name);
+    let target = models_dir.join(&filename);
+
+    // Un enlace duro no ocupa espacio extra; entre sistemas de archivos se copia
+    if fs::hard_link(source, &target).await.is_err() {
+        fs::copy(source, &target)
+            .await
+            .map_err(|e| AudioInkError::FileError(e.to_string()))?;
+    }
+
+    let to_validate = target.clone();
+    let validation = tokio::task::spawn_blocking(move || validate(to_validate))
+        .await
+        .map_err(|e| AudioInkError::Internal(format!("Error de task: {}", e)))
+        .and_then(|result| result);
+    if let Err(e) = validation {
+        let _ = fs::remove_file(&target).await;
+        return Err(e);
+    }
+
+    let size_bytes = fs::metadata(&target)
+        .await
+        .map_err(|e| AudioInkError::FileError(e.to_string()))?
+        .len();
+    let model = CustomModel {
+        name,
+        display_name: display_name.to_string(),
+        filename,
+        size_bytes,
+        imported_at: Utc::now(),
+    };
+
+    let mut models = read_manifest(models_dir);
+    models.push(model.clone());
+    write_manifest(models_dir, &models).await?;
+
+    Ok(model)
+}
+
+/// Identificador a partir del nombre elegido: "Medical v2" → "medical-v2"
+fn model_name_from(display_name: &str) -> String {
+    let mut name = String::new();
+    for c in display_name.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            name.push(c);
+        } else if !name.is_empty() && !name.ends_with('-') {
+            name.push('-');
+        }
+    }
+    name.trim_end_matches('-').to_string()
+}
+
+/// El nombre no puede tapar a un modelo del catálogo ni repetir uno importado
+fn ensure_name_available(models_dir: &Path, name: &str) -> AudioInkResult<()> {
+    let taken = WhisperModel::from_name(name).is_some()
+        || read_manifest(models_dir).iter().any(|m| m.name == name);
+    if taken {
+        return Err(AudioInkError::FileError(format!(
+            "Ya existe un modelo llamado '{}'",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Comprueba que el archivo empieza con la cabecera de un modelo ggml/gguf
+async fn check_model_header(path: &Path) -> AudioInkResult<()> {
+    let mut file = fs::File::open(path)
+        .await
+        .map_err(|e| AudioInkError::FileError(format!("{}: {}", path.display(), e)))?;
+    let mut magic = [0u8; 4];
+    let header_ok = file.read_exact(&mut magic).await.is_ok() && MODEL_MAGICS.contains(&&magic);
+    if !header_ok {
+        return Err(AudioInkError::UnsupportedFormat(format!(
+            "{} no es un modelo ggml/gguf de Whisper",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+fn read_manifest(models_dir: &Path) -> Vec<CustomModel> {
+    std::fs::read_to_string(models_dir.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+async fn write_manifest(models_dir: &Path, models: &[CustomModel]) -> AudioInkResult<()> {
+    let json = serde_json::to_string_pretty(models)?;
+    let path = models_dir.join(MANIFEST_FILE);
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, json).await?;
+    fs::rename(&temp_path, &path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn write_model(dir: &Path, header: &[u8]) -> PathBuf {
+        let path = dir.join("source.bin");
+        let mut content = header.to_vec();
+        content.extend_from_slice(&[0u8; 64]);
+        fs::write(&path, content).await.unwrap();
+        path
+    }
+
+    #[test]
+    fn test_model_name_from() {
+        assert_eq!(model_name_from("Medical v2"), "medical-v2");
+        assert_eq!(model_name_from("  --Whisper (fine-tuned)  "), "whisper-fine-tuned");
+        assert_eq!(model_name_from("!!!"), "");
+    }
+
+    #[tokio::test]
+    async fn test_import_registers_model() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let models_dir = tempfile::tempdir().unwrap();
+        let source = write_model(source_dir.path(), b"lmgg").await;
+
+        let model = import_model_into(models_dir.path(), &source, "Medical v2", |_| Ok(()))
+            .await
+            .unwrap();
+        assert_eq!(model.name, "medical-v2");
+        assert_eq!(model.size_bytes, 68);
+        assert!(models_dir.path().join(&model.filename).exists());
+        assert_eq!(read_manifest(models_dir.path()), vec![model]);
+
+        // El mismo nombre no se puede importar dos veces, ni tapar uno del catálogo
+        let again = import_model_into(models_dir.path(), &source, "medical V2", |_| Ok(())).await;
+        assert!(again.is_err());
+        let builtin = import_model_into(models_dir.path(), &source, "Base", |_| Ok(())).await;
+        assert!(builtin.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_imports_with_same_name() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let models_dir = tempfile::tempdir().unwrap();
+        let source = write_model(source_dir.path(), b"lmgg").await;
+
+        let (first, second) = tokio::join!(
+            import_model_into(models_dir.path(), &source, "Medical", |_| Ok(())),
+            import_model_into(models_dir.path(), &source, "medical", |_| Ok(())),
+        );
+
+        assert!(first.is_ok() != second.is_ok());
+        assert_eq!(read_manifest(models_dir.path()).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_invalid_files() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let models_dir = tempfile::tempdir().unwrap();
+
+        let not_a_model = write_model(source_dir.path(), b"RIFF").await;
+        let result = import_model_into(models_dir.path(), &not_a_model, "wav", |_| Ok(())).await;
+        assert!(matches!(result, Err(AudioInkError::UnsupportedFormat(_))));
+
+        // Cabecera correcta pero whisper.cpp no lo carga: no queda nada registrado
+        let broken = write_model(source_dir.path(), b"lmgg").await;
+        let result = import_model_into(models_dir.path(), &broken, "broken", |_| {
+            Err(AudioInkError::Whisper("invalid model".to_string()))
+        })
+        .await;
+        assert!(matches!(result, Err(AudioInkError::Whisper(_))));
+        assert!(!models_dir.path().join("custom-broken.bin").exists());
+        assert!(read_manifest(models_dir.path()).is_empty());
+    }
+}
@@ -0,0 +1,213 @@
+use crate::core::WhisperEngine;
+use crate::models::WhisperModel;
+use crate::utils::{AudioInkError, AudioInkResult};
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, Mutex};
+
+/// Modelo cargado en el pool
+#[derive(Debug, Clone)]
+pub struct PooledModel {
+    pub model: WhisperModel,
+    /// Memoria estimada (tamaño del modelo)
+    pub size_bytes: u64,
+    /// Alguna transcripción está usando el motor
+    pub in_use: bool,
+    pub last_used: DateTime<Utc>,
+}
+
+struct PoolEntry<E> {
+    model: WhisperModel,
+    engine: Arc<E>,
+    size_bytes: u64,
+    last_used: DateTime<Utc>,
+}
+
+impl<E> PoolEntry<E> {
+    /// El pool tiene la única referencia: ninguna transcripción lo usa
+    fn is_idle(&self) -> bool {
+        Arc::strong_count(&self.engine) == 1
+    }
+}
+
+/// Motores de Whisper cargados, hasta un presupuesto de memoria
+///
+/// Al cargar un modelo se descargan los menos usados recientemente hasta que
+/// el nuevo cabe en el presupuesto. Los motores en uso nunca se descargan, así
+/// que el presupuesto puede superarse mientras varias transcripciones usan
+/// modelos distintos; y un modelo mayor que el presupuesto se carga igualmente.
+pub struct EnginePool<E = WhisperEngine> {
+    /// Ordenados del menos al más usado recientemente
+    entries: Mutex<Vec<PoolEntry<E>>>,
+}
+
+impl<E> EnginePool<E> {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Devuelve el motor del modelo, cargándolo con `load` si hace falta
+    ///
+    /// La carga se hace fuera del lock para no bloquear a las transcripciones
+    /// que usan otros modelos.
+    pub fn get_or_load(
+        &self,
+        model: &WhisperModel,
+        budget_bytes: u64,
+        load: impl FnOnce() -> AudioInkResult<E>,
+    ) -> AudioInkResult<Arc<E>> {
+        let size_bytes = model.size_bytes();
+        {
+            let mut entries = self.lock()?;
+            if let Some(engine) = touch(&mut entries, model) {
+                return Ok(engine);
+            }
+            evict_for(&mut entries, size_bytes, budget_bytes);
+        }
+
+        let engine = Arc::new(load()?);
+
+        let mut entries = self.lock()?;
+        // Otra transcripción pudo cargar el mismo modelo mientras tanto
+        if let Some(existing) = touch(&mut entries, model) {
+            return Ok(existing);
+        }
+        evict_for(&mut entries, size_bytes, budget_bytes);
+        entries.push(PoolEntry {
+            model: model.clone(),
+            engine: engine.clone(),
+            size_bytes,
+            last_used: Utc::now(),
+        });
+        Ok(engine)
+    }
+
+    /// Quita un modelo del pool. Devuelve false si no estaba cargado
+    ///
+    /// Las transcripciones en curso con ese modelo terminan normalmente; la
+    /// memoria se libera cuando acaba la última.
+    pub fn unload(&self, model: &WhisperModel) -> AudioInkResult<bool> {
+        let mut entries = self.lock()?;
+        let before = entries.len();
+        entries.retain(|entry| entry.model != *model);
+        Ok(entries.len() != before)
+    }
+
+    /// Modelos cargados, del más al menos usado recientemente
+    pub fn loaded(&self) -> AudioInkResult<Vec<PooledModel>> {
+        let entries = self.lock()?;
+        Ok(entries
+            .iter()
+            .rev()
+            .map(|entry| PooledModel {
+                model: entry.model.clone(),
+                size_bytes: entry.size_bytes,
+                in_use: !entry.is_idle(),
+                last_used: entry.last_used,
+            })
+            .collect())
+    }
+
+    fn lock(&self) -> AudioInkResult<std::sync::MutexGuard<'_, Vec<PoolEntry<E>>>> {
+        self.entries
+            .lock()
+            .map_err(|e| AudioInkError::Internal(format!("Error de lock: {}", e)))
+    }
+}
+
+impl<E> Default for EnginePool<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Marca el modelo como recién usado y devuelve su motor
+fn touch<E>(entries: &mut Vec<PoolEntry<E>>, model: &WhisperModel) -> Option<Arc<E>> {
+    let index = entries.iter().position(|entry| entry.model == *model)?;
+    let mut entry = entries.remove(index);
+    entry.last_used = Utc::now();
+    let engine = entry.engine.clone();
+    entries.push(entry);
+    Some(engine)
+}
+
+/// Descarga motores inactivos, del menos usado al más, hasta que quepan `size_bytes`
+fn evict_for<E>(entries: &mut Vec<PoolEntry<E>>, size_bytes: u64, budget_bytes: u64) {
+    let mut used: u64 = entries.iter().map(|entry| entry.size_bytes).sum();
+    let mut index = 0;
+    while used + size_bytes > budget_bytes && index < entries.len() {
+        if entries[index].is_idle() {
+            used -= entries.remove(index).size_bytes;
+        } else {
+            index += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Motor de prueba: solo recuerda qué modelo representa
+    struct FakeEngine(WhisperModel);
+
+    fn load(pool: &EnginePool<FakeEngine>, model: WhisperModel, budget: u64) -> Arc<FakeEngine> {
+        pool.get_or_load(&model, budget, || Ok(FakeEngine(model.clone())))
+            .unwrap()
+    }
+
+    fn loaded(pool: &EnginePool<FakeEngine>) -> Vec<WhisperModel> {
+        pool.loaded().unwrap().into_iter().map(|m| m.model).collect()
+    }
+
+    #[test]
+    fn test_pool_reuses_loaded_engine() {
+        let pool = EnginePool::new();
+        let first = load(&pool, WhisperModel::BASE, u64::MAX);
+        let second = pool
+            .get_or_load(&WhisperModel::BASE, u64::MAX, || panic!("should not reload"))
+            .unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(second.0, WhisperModel::BASE);
+    }
+
+    #[test]
+    fn test_pool_evicts_least_recently_used() {
+        let pool = EnginePool::new();
+        let budget = WhisperModel::BASE.size_bytes() + WhisperModel::SMALL.size_bytes();
+
+        load(&pool, WhisperModel::TINY, budget);
+        load(&pool, WhisperModel::BASE, budget);
+        load(&pool, WhisperModel::TINY, budget); // Base pasa a ser el menos reciente
+        load(&pool, WhisperModel::SMALL, budget);
+
+        assert_eq!(loaded(&pool), vec![WhisperModel::SMALL, WhisperModel::TINY]);
+    }
+
+    #[test]
+    fn test_pool_keeps_engines_in_use() {
+        let pool = EnginePool::new();
+        let budget = WhisperModel::SMALL.size_bytes();
+
+        let in_use = load(&pool, WhisperModel::SMALL, budget);
+        load(&pool, WhisperModel::BASE, budget);
+
+        let models = pool.loaded().unwrap();
+        assert_eq!(models.len(), 2, "engines in use are not evicted");
+        assert!(models.iter().any(|m| m.model == WhisperModel::SMALL && m.in_use));
+
+        drop(in_use);
+        load(&pool, WhisperModel::TINY, budget);
+        assert!(!loaded(&pool).contains(&WhisperModel::SMALL));
+    }
+
+    #[test]
+    fn test_pool_unload() {
+        let pool = EnginePool::new();
+        load(&pool, WhisperModel::BASE, u64::MAX);
+        assert!(pool.unload(&WhisperModel::BASE).unwrap());
+        assert!(!pool.unload(&WhisperModel::BASE).unwrap());
+        assert!(loaded(&pool).is_empty());
+    }
+}
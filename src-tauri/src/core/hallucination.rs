@@ -0,0 +1,258 @@
+//! Filtro de alucinaciones de Whisper
+//!
+//! En archivos largos Whisper puede quedarse repitiendo la misma frase durante
+//! minutos, y en tramos sin voz inventa texto ("Subtítulos por ..."). El filtro
+//! se aplica a los segmentos ya transcritos y los elimina o los marca según
+//! [`HallucinationFilterMode`].
+
+use crate::core::whisper::normalize_word;
+use crate::models::{
+    FilteredSegment, HallucinationFilter, HallucinationFilterMode, HallucinationReason,
+    HallucinationReport, Segment,
+};
+use std::ops::Range;
+
+/// N-grama más largo que se busca al detectar bucles dentro de un segmento
+const MAX_NGRAM_WORDS: usize = 8;
+
+/// Palabras mínimas de un bucle dentro de un segmento (evita marcar un "no, no, no")
+const MIN_LOOP_WORDS: usize = 8;
+
+/// Segmentos que puede abarcar un ciclo repetido entre segmentos (A B A B A B)
+const MAX_CYCLE_SEGMENTS: usize = 4;
+
+/// Aplica el filtro a los segmentos y devuelve el informe (None si está desactivado)
+///
+/// En modo `flag` los segmentos se conservan con `hallucination` indicando el
+/// motivo; en modo `drop` se eliminan.
+pub fn filter_hallucinations(
+    segments: &mut Vec<Segment>,
+    filter: &HallucinationFilter,
+) -> Option<HallucinationReport> {
+    if filter.mode == HallucinationFilterMode::Off {
+        return None;
+    }
+
+    let reasons = detect_hallucinations(segments, filter);
+    let mut report = HallucinationReport {
+        mode: filter.mode,
+        segments: Vec::new(),
+    };
+
+    for (segment, reason) in segments.iter_mut().zip(reasons) {
+        let Some(reason) = reason else {
+            continue;
+        };
+        report.segments.push(FilteredSegment {
+            start_ms: segment.start_ms,
+            end_ms: segment.end_ms,
+            text: segment.text.clone(),
+            reason,
+        });
+        segment.hallucination = Some(reason);
+    }
+
+    if filter.mode == HallucinationFilterMode::Drop {
+        segments.retain(|segment| segment.hallucination.is_none());
+    }
+
+    Some(report)
+}
+
+/// Motivo de alucinación de cada segmento (None si parece legítimo)
+fn detect_hallucinations(
+    segments: &[Segment],
+    filter: &HallucinationFilter,
+) -> Vec<Option<HallucinationReason>> {
+    let min_repeats = filter.min_repeats.max(2);
+    let mut reasons = vec![None; segments.len()];
+
+    // Bucles entre segmentos: se conserva la primera aparición del texto
+    let texts: Vec<String> = segments.iter().map(|s| normalize_text(&s.text)).collect();
+    for run in repeated_runs(&texts, MAX_CYCLE_SEGMENTS, |_| min_repeats) {
+        if texts[run.start].is_empty() {
+            continue;
+        }
+        for reason in &mut reasons[run] {
+            *reason = Some(HallucinationReason::Repetition);
+        }
+    }
+
+    for (segment, reason) in segments.iter().zip(reasons.iter_mut()) {
+        if reason.is_none() {
+            *reason = segment_reason(segment, filter, min_repeats);
+        }
+    }
+
+    reasons
+}
+
+/// Comprueba un segmento por sí solo: bucle interno, silencio o baja confianza
+fn segment_reason(
+    segment: &Segment,
+    filter: &HallucinationFilter,
+    min_repeats: usize,
+) -> Option<HallucinationReason> {
+    let words: Vec<String> = segment
+        .text
+        .split_whitespace()
+        .map(normalize_word)
+        .filter(|w| !w.is_empty())
+        .collect();
+    let loop_repeats = |n: usize| min_repeats.max(MIN_LOOP_WORDS.div_ceil(n));
+    if !repeated_runs(&words, MAX_NGRAM_WORDS, loop_repeats).is_empty() {
+        return Some(HallucinationReason::Repetition);
+    }
+
+    if segment
+        .no_speech_prob
+        .is_some_and(|p| p > filter.no_speech_threshold)
+    {
+        return Some(HallucinationReason::NoSpeech);
+    }
+
+    if segment
+        .avg_logprob
+        .is_some_and(|p| p < filter.logprob_threshold)
+    {
+        return Some(HallucinationReason::LowConfidence);
+    }
+
+    None
+}
+
+/// Texto normalizado para comparar segmentos
+fn normalize_text(text: &str) -> String {
+    text.split_whitespace()
+        .map(normalize_word)
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Busca n-gramas (de 1 a `max_n` elementos) repetidos de forma consecutiva al
+/// menos `min_repeats(n)` veces
+///
+/// Devuelve, para cada bucle, el rango de elementos que repiten la primera
+/// aparición (que no se incluye). Si varios tamaños encajan en la misma
+/// posición gana el que cubre más elementos.
+fn repeated_runs<T: PartialEq>(
+    items: &[T],
+    max_n: usize,
+    min_repeats: impl Fn(usize) -> usize,
+) -> Vec<Range<usize>> {
+    let mut runs = Vec::new();
+    let mut i = 0;
+
+    while i < items.len() {
+        let mut best: Option<(usize, usize)> = None; // (n, repeticiones)
+        for n in 1..=max_n {
+            if i + 2 * n > items.len() {
+                break;
+            }
+            let pattern = &items[i..i + n];
+            let mut repeats = 1;
+            while i + (repeats + 1) * n <= items.len()
+                && items[i + repeats * n..i + (repeats + 1) * n] == *pattern
+            {
+                repeats += 1;
+            }
+            let covers_more = best.is_none_or(|(best_n, best_repeats)| n * repeats > best_n * best_repeats);
+            if repeats >= min_repeats(n) && covers_more {
+                best = Some((n, repeats));
+            }
+        }
+
+        match best {
+            Some((n, repeats)) => {
+                runs.push(i + n..i + n * repeats);
+                i += n * repeats;
+            }
+            None => i += 1,
+        }
+    }
+
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start_ms: i64, text: &str) -> Segment {
+        Segment::new(start_ms, start_ms + 1000, text)
+    }
+
+    fn filter(mode: HallucinationFilterMode) -> HallucinationFilter {
+        HallucinationFilter {
+            mode,
+            ..HallucinationFilter::default()
+        }
+    }
+
+    #[test]
+    fn test_repeated_runs() {
+        let items = ["a", "b", "c", "b", "c", "b", "c", "d"];
+        assert_eq!(repeated_runs(&items, 3, |_| 3), vec![3..7]);
+        assert!(repeated_runs(&items, 3, |_| 4).is_empty());
+        assert_eq!(repeated_runs(&["x"; 5], 2, |_| 3), vec![1..5]);
+    }
+
+    #[test]
+    fn test_drops_repeated_segments_keeping_first() {
+        let mut segments = vec![
+            segment(0, "Welcome back."),
+            segment(1000, "Thanks for watching!"),
+            segment(2000, "thanks for watching"),
+            segment(3000, "Thanks for watching!"),
+            segment(4000, "Bye."),
+        ];
+        let report = filter_hallucinations(&mut segments, &filter(HallucinationFilterMode::Drop)).unwrap();
+
+        let texts: Vec<&str> = segments.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, ["Welcome back.", "Thanks for watching!", "Bye."]);
+        assert_eq!(report.segments.len(), 2);
+        assert!(report
+            .segments
+            .iter()
+            .all(|s| s.reason == HallucinationReason::Repetition));
+    }
+
+    #[test]
+    fn test_flags_loops_silence_and_low_confidence() {
+        let mut looped = segment(0, "I think I think I think I think I think");
+        looped.avg_logprob = Some(-0.2);
+        let mut silent = segment(1000, "Subtitles by the Amara.org community");
+        silent.no_speech_prob = Some(0.95);
+        let mut unsure = segment(2000, "mumbled words");
+        unsure.avg_logprob = Some(-1.6);
+        let mut fine = segment(3000, "No, no, no, that's not it.");
+        fine.no_speech_prob = Some(0.1);
+        fine.avg_logprob = Some(-0.3);
+
+        let mut segments = vec![looped, silent, unsure, fine];
+        let report = filter_hallucinations(&mut segments, &filter(HallucinationFilterMode::Flag)).unwrap();
+
+        assert_eq!(segments.len(), 4);
+        let reasons: Vec<_> = segments.iter().map(|s| s.hallucination).collect();
+        assert_eq!(
+            reasons,
+            [
+                Some(HallucinationReason::Repetition),
+                Some(HallucinationReason::NoSpeech),
+                Some(HallucinationReason::LowConfidence),
+                None,
+            ]
+        );
+        assert_eq!(report.mode, HallucinationFilterMode::Flag);
+        assert_eq!(report.segments.len(), 3);
+    }
+
+    #[test]
+    fn test_filter_off_leaves_segments_untouched() {
+        let mut segments = vec![segment(0, "again"), segment(1000, "again"), segment(2000, "again")];
+        assert!(filter_hallucinations(&mut segments, &filter(HallucinationFilterMode::Off)).is_none());
+        assert_eq!(segments.len(), 3);
+        assert!(segments.iter().all(|s| s.hallucination.is_none()));
+    }
+}
@@ -1,11 +1,25 @@
 pub mod audio;
+pub mod cancel;
+pub mod checksums;
+pub mod custom_models;
+pub mod engine_pool;
+pub mod hallucination;
 pub mod whisper;
 pub mod models_manager;
+pub mod parallel;
 pub mod youtube_dl;
 pub mod speedup;
+pub mod storage;
 
 pub use audio::*;
+pub use cancel::*;
+pub use checksums::*;
+pub use custom_models::*;
+pub use engine_pool::*;
+pub use hallucination::*;
 pub use whisper::*;
 pub use models_manager::*;
+pub use parallel::*;
 pub use youtube_dl::*;
 pub use speedup::*;
+pub use storage::*;
@@ -1,10 +1,57 @@
-use crate::models::WhisperModel;
+use crate::core::{
+    check_file_sha256, ensure_free_space, CancellationToken, expected_checksum, fetch_published_sha256, find_custom_model,
+    forget_checksum, list_custom_models, record_checksum, remove_custom_model,
+};
+use crate::models::{AppSettings, WhisperModel, DEFAULT_MODEL_BASE_URL};
 use crate::utils::{AudioInkError, AudioInkResult};
 use directories::ProjectDirs;
 use futures_util::StreamExt;
-use std::path::PathBuf;
+use reqwest::header::{CONTENT_RANGE, RANGE};
+use reqwest::StatusCode;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use std::sync::{Mutex, RwLock};
 use tokio::fs;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::{watch, Semaphore};
+
+/// De dónde se descargan los modelos y dónde más se buscan
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelSources {
+    /// URL base de descarga (http(s) o `file://`). None = Hugging Face
+    pub base_url: Option<String>,
+    /// Directorios de solo lectura con modelos ya descargados
+    pub extra_dirs: Vec<PathBuf>,
+}
+
+impl From<&AppSettings> for ModelSources {
+    fn from(settings: &AppSettings) -> Self {
+        Self {
+            base_url: settings.model_mirror_url.clone(),
+            extra_dirs: settings.extra_model_dirs.clone(),
+        }
+    }
+}
+
+static MODEL_SOURCES: RwLock<ModelSources> = RwLock::new(ModelSources {
+    base_url: None,
+    extra_dirs: Vec::new(),
+});
+
+/// Cambia el origen de descarga y los directorios extra de modelos
+pub fn set_model_sources(sources: ModelSources) {
+    if let Ok(mut current) = MODEL_SOURCES.write() {
+        *current = sources;
+    }
+}
+
+/// Origen de descarga y directorios extra actuales
+pub fn model_sources() -> ModelSources {
+    MODEL_SOURCES
+        .read()
+        .map(|sources| sources.clone())
+        .unwrap_or_default()
+}
 
 /// Obtiene el directorio donde se almacenan los modelos
 pub fn get_models_dir() -> PathBuf {
@@ -17,8 +64,19 @@ pub fn get_models_dir() -> PathBuf {
 }
 
 /// Obtiene la ruta completa de un modelo
+/// Si no está en el directorio de modelos, se busca en los directorios extra
 pub fn get_model_path(model: &WhisperModel) -> PathBuf {
-    get_models_dir().join(model.filename())
+    let path = get_models_dir().join(model.filename());
+    if path.exists() {
+        return path;
+    }
+
+    model_sources()
+        .extra_dirs
+        .iter()
+        .map(|dir| dir.join(model.filename()))
+        .find(|candidate| candidate.exists())
+        .unwrap_or(path)
 }
 
 /// Verifica si un modelo está descargado
@@ -27,21 +85,40 @@ pub fn is_model_downloaded(model: &WhisperModel) -> bool {
     path.exists()
 }
 
-/// Lista los modelos descargados
+/// Busca un modelo por nombre: primero en el catálogo, luego entre los importados
+pub fn resolve_model(name: &str) -> Option<WhisperModel> {
+    WhisperModel::from_name(name).or_else(|| find_custom_model(name).map(WhisperModel::Custom))
+}
+
+/// Lista los modelos del catálogo seguidos de los importados
+pub fn list_all_models() -> Vec<WhisperModel> {
+    let mut models = WhisperModel::all();
+    models.extend(list_custom_models().into_iter().map(WhisperModel::Custom));
+    models
+}
+
+/// Lista los modelos descargados (incluye los importados)
 pub fn list_downloaded_models() -> Vec<WhisperModel> {
-    WhisperModel::all()
+    list_all_models()
         .into_iter()
-        .filter(|m| is_model_downloaded(m))
+        .filter(is_model_downloaded)
         .collect()
 }
 
+/// Archivo parcial de la descarga de un modelo, que se continúa en el próximo intento
+fn partial_download_path(model: &WhisperModel) -> PathBuf {
+    get_models_dir().join(model.filename()).with_extension("downloading")
+}
+
 /// Callback para reportar progreso de descarga
 pub type DownloadProgressCallback = Box<dyn Fn(f32, u64, u64) + Send + Sync>;
 
 /// Descarga un modelo de Whisper
+/// Al cancelarla se borra el archivo parcial
 pub async fn download_model(
     model: &WhisperModel,
     on_progress: Option<DownloadProgressCallback>,
+    cancel: &CancellationToken,
 ) -> AudioInkResult<PathBuf> {
     let models_dir = get_models_dir();
 
@@ -50,42 +127,390 @@ pub async fn download_model(
         .await
         .map_err(|e| AudioInkError::FileError(e.to_string()))?;
 
+    // Si ya existe (aquí o en un directorio extra), retornar la ruta
+    let existing = get_model_path(model);
+    if existing.exists() {
+        return Ok(existing);
+    }
     let model_path = models_dir.join(model.filename());
 
-    // Si ya existe, retornar la ruta
-    if model_path.exists() {
-        return Ok(model_path);
+    // Los modelos importados no se pueden descargar de nuevo
+    let base_url = model_sources().base_url;
+    let Some(url) = model.download_url_from(base_url.as_deref().unwrap_or(DEFAULT_MODEL_BASE_URL))
+    else {
+        return Err(AudioInkError::ModelNotFound(format!(
+            "El archivo del modelo importado '{}' no existe",
+            model
+        )));
+    };
+
+    // Archivo temporal para descarga. Si quedó uno de un intento anterior, se continúa
+    let temp_path = partial_download_path(model);
+    let downloaded = async {
+        // Hash fijado en el catálogo o anotado antes; si no, el que publica Hugging
+        // Face, pero solo al descargar de allí: con un mirror no se sale a internet
+        let expected = match expected_checksum(model) {
+            Some(sha256) => Some(sha256),
+            None if base_url.is_none() => fetch_published_sha256(&url, cancel).await?,
+            None => None,
+        };
+        download_file(&url, &temp_path, model.size_bytes(), on_progress.as_ref(), cancel).await?;
+        Ok(expected)
     }
+    .await;
+    let expected = match downloaded {
+        Ok(expected) => expected,
+        Err(e) => {
+            if matches!(e, AudioInkError::Cancelled) {
+                let _ = fs::remove_file(&temp_path).await;
+            }
+            return Err(e);
+        }
+    };
 
-    let url = model.download_url();
-    let client = reqwest::Client::new();
+    // Un archivo corrupto no se reanuda: el próximo intento empieza de cero
+    if let Err(e) = check_file_sha256(&temp_path, expected.as_deref(), model).await {
+        let _ = fs::remove_file(&temp_path).await;
+        return Err(e);
+    }
 
-    let response = client
-        .get(&url)
-        .send()
+    // Renombrar archivo temporal al nombre final
+    fs::rename(&temp_path, &model_path)
         .await
-        .map_err(|e| AudioInkError::ModelDownload(e.to_string()))?;
+        .map_err(|e| AudioInkError::FileError(e.to_string()))?;
 
-    if !response.status().is_success() {
-        return Err(AudioInkError::ModelDownload(format!(
-            "Error al descargar modelo: HTTP {}",
-            response.status()
-        )));
+    // Solo se anota un hash conocido, nunca el del propio archivo descargado
+    if let Some(sha256) = expected {
+        record_checksum(model.filename(), &sha256).await?;
     }
 
-    let total_size = response.content_length().unwrap_or(model.size_bytes());
+    Ok(model_path)
+}
+
+/// Estado final de una descarga, compartido con quien espera la misma
+#[derive(Debug, Clone)]
+enum DownloadOutcome {
+    Done(PathBuf),
+    Cancelled,
+    Failed(String),
+}
 
-    // Archivo temporal para descarga
-    let temp_path = model_path.with_extension("downloading");
-    let mut file = fs::File::create(&temp_path)
-        .await
-        .map_err(|e| AudioInkError::FileError(e.to_string()))?;
+impl DownloadOutcome {
+    fn from_result(result: &AudioInkResult<PathBuf>) -> Self {
+        match result {
+            Ok(path) => DownloadOutcome::Done(path.clone()),
+            Err(AudioInkError::Cancelled) => DownloadOutcome::Cancelled,
+            Err(e) => DownloadOutcome::Failed(e.to_string()),
+        }
+    }
 
-    let mut downloaded: u64 = 0;
-    let mut stream = response.bytes_stream();
+    fn into_result(self) -> AudioInkResult<PathBuf> {
+        match self {
+            DownloadOutcome::Done(path) => Ok(path),
+            DownloadOutcome::Cancelled => Err(AudioInkError::Cancelled),
+            DownloadOutcome::Failed(message) => Err(AudioInkError::ModelDownload(message)),
+        }
+    }
+}
+
+/// Descarga registrada en el gestor, en cola o en curso
+struct ActiveDownload {
+    id: String,
+    model: String,
+    started: bool,
+    /// Archivo parcial que se borra al cancelar
+    partial: PathBuf,
+    cancel: CancellationToken,
+    outcome: watch::Receiver<Option<DownloadOutcome>>,
+}
+
+/// Descarga en cola o en curso, para el frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadInfo {
+    pub id: String,
+    pub model: String,
+    /// Esperando a que termine la descarga anterior
+    pub queued: bool,
+}
+
+/// Turno de descarga devuelto por [`DownloadManager::enqueue`]
+pub struct DownloadTicket {
+    /// Id con el que se etiquetan los eventos y se cancela
+    pub id: String,
+    /// El modelo ya se estaba descargando: se espera a esa descarga
+    pub joined: bool,
+    model: WhisperModel,
+    cancel: CancellationToken,
+    outcome: watch::Receiver<Option<DownloadOutcome>>,
+    /// Solo lo tiene quien hace la descarga
+    sender: Option<watch::Sender<Option<DownloadOutcome>>>,
+}
+
+/// Gestor de descargas de modelos
+///
+/// Cada modelo se descarga una sola vez aunque se pida varias, y las descargas
+/// van de una en una, en orden de llegada.
+pub struct DownloadManager {
+    downloads: Mutex<Vec<ActiveDownload>>,
+    /// El semáforo de tokio es justo: atiende las esperas en orden
+    slot: Semaphore,
+}
+
+impl Default for DownloadManager {
+    fn default() -> Self {
+        Self {
+            downloads: Mutex::new(Vec::new()),
+            slot: Semaphore::new(1),
+        }
+    }
+}
+
+impl DownloadManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pone un modelo en la cola, o se une a su descarga si ya está en ella
+    pub fn enqueue(&self, model: &WhisperModel) -> AudioInkResult<DownloadTicket> {
+        let mut downloads = self.lock()?;
+
+        if let Some(active) = downloads.iter().find(|d| d.model == model.name()) {
+            return Ok(DownloadTicket {
+                id: active.id.clone(),
+                joined: true,
+                model: model.clone(),
+                cancel: active.cancel.clone(),
+                outcome: active.outcome.clone(),
+                sender: None,
+            });
+        }
+
+        let (sender, outcome) = watch::channel(None);
+        let ticket = DownloadTicket {
+            id: uuid::Uuid::new_v4().to_string(),
+            joined: false,
+            model: model.clone(),
+            cancel: CancellationToken::new(),
+            outcome,
+            sender: Some(sender),
+        };
+        downloads.push(ActiveDownload {
+            id: ticket.id.clone(),
+            model: model.name().to_string(),
+            started: false,
+            partial: partial_download_path(model),
+            cancel: ticket.cancel.clone(),
+            outcome: ticket.outcome.clone(),
+        });
+        Ok(ticket)
+    }
+
+    /// Espera su turno y descarga, o espera a la descarga a la que se unió
+    pub async fn wait(
+        &self,
+        ticket: DownloadTicket,
+        on_progress: Option<DownloadProgressCallback>,
+    ) -> AudioInkResult<PathBuf> {
+        let DownloadTicket {
+            id,
+            model,
+            cancel,
+            mut outcome,
+            sender,
+            ..
+        } = ticket;
+
+        let Some(sender) = sender else {
+            let outcome = outcome
+                .wait_for(Option::is_some)
+                .await
+                .map_err(|_| AudioInkError::Internal("La descarga terminó sin resultado".into()))?
+                .clone();
+            return outcome.map_or(Err(AudioInkError::Cancelled), DownloadOutcome::into_result);
+        };
+
+        let result = self.run(&id, &model, on_progress, &cancel).await;
+        if let Ok(mut downloads) = self.downloads.lock() {
+            if let Some(index) = downloads.iter().position(|d| d.id == id) {
+                let download = downloads.remove(index);
+                // Ya no escribe nadie en el parcial, aunque se cancelara a media descarga
+                if matches!(result, Err(AudioInkError::Cancelled)) {
+                    let _ = std::fs::remove_file(&download.partial);
+                }
+            }
+        }
+        sender.send_replace(Some(DownloadOutcome::from_result(&result)));
+        result
+    }
+
+    async fn run(
+        &self,
+        id: &str,
+        model: &WhisperModel,
+        on_progress: Option<DownloadProgressCallback>,
+        cancel: &CancellationToken,
+    ) -> AudioInkResult<PathBuf> {
+        let _permit = tokio::select! {
+            permit = self.slot.acquire() => {
+                permit.map_err(|e| AudioInkError::Internal(e.to_string()))?
+            }
+            _ = cancel.cancelled() => return Err(AudioInkError::Cancelled),
+        };
+
+        if let Some(download) = self.lock()?.iter_mut().find(|d| d.id == id) {
+            download.started = true;
+        }
+        download_model(model, on_progress, cancel).await
+    }
+
+    /// Cancela una descarga en cola o en curso y borra su archivo parcial
+    /// Devuelve false si el id no existe
+    pub fn cancel(&self, id: &str) -> AudioInkResult<bool> {
+        let downloads = self.lock()?;
+        let Some(download) = downloads.iter().find(|d| d.id == id) else {
+            return Ok(false);
+        };
+        download.cancel.cancel();
+        // En cola nadie escribe en el parcial; si está en curso lo borra la propia
+        // descarga al terminar
+        if !download.started {
+            let _ = std::fs::remove_file(&download.partial);
+        }
+        Ok(true)
+    }
+
+    /// Descargas en curso y en cola, en orden de llegada
+    pub fn list(&self) -> AudioInkResult<Vec<DownloadInfo>> {
+        Ok(self
+            .lock()?
+            .iter()
+            .map(|d| DownloadInfo {
+                id: d.id.clone(),
+                model: d.model.clone(),
+                queued: !d.started,
+            })
+            .collect())
+    }
+
+    fn lock(&self) -> AudioInkResult<std::sync::MutexGuard<'_, Vec<ActiveDownload>>> {
+        self.downloads
+            .lock()
+            .map_err(|e| AudioInkError::Internal(format!("Error de lock: {}", e)))
+    }
+}
+
+/// Descarga `url` en `temp_path`, continuando desde lo que ya tenga el archivo
+///
+/// Pide el resto con una cabecera `Range`; si el servidor la ignora (200) o el
+/// parcial no le cuadra, vuelve a empezar desde cero. Ante un corte de conexión
+/// el parcial se conserva para el próximo intento.
+async fn download_file(
+    url: &str,
+    temp_path: &Path,
+    size_hint: u64,
+    on_progress: Option<&DownloadProgressCallback>,
+    cancel: &CancellationToken,
+) -> AudioInkResult<()> {
+    cancel.check()?;
+    if url.starts_with("file://") {
+        return copy_local_file(url, temp_path, on_progress, cancel).await;
+    }
+
+    let client = reqwest::Client::new();
+    let mut resume_from = match fs::metadata(temp_path).await {
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    };
+
+    let (response, total_size) = loop {
+        let mut request = client.get(url);
+        if resume_from > 0 {
+            request = request.header(RANGE, format!("bytes={}-", resume_from));
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AudioInkError::ModelDownload(e.to_string()))?;
 
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| AudioInkError::ModelDownload(e.to_string()))?;
+        let content_range = response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_content_range);
+
+        match response.status() {
+            StatusCode::PARTIAL_CONTENT if resume_from > 0 => {
+                if let Some((Some(start), total)) = content_range {
+                    if start == resume_from {
+                        let total = total
+                            .or_else(|| response.content_length().map(|len| start + len))
+                            .unwrap_or(size_hint);
+                        break (response, total);
+                    }
+                }
+                // El servidor devolvió otro rango: no se puede unir al parcial
+            }
+            // El parcial ya estaba completo
+            StatusCode::RANGE_NOT_SATISFIABLE if resume_from > 0 => {
+                if let Some((_, Some(total))) = content_range {
+                    if total == resume_from {
+                        if let Some(callback) = on_progress {
+                            callback(1.0, total, total);
+                        }
+                        return Ok(());
+                    }
+                }
+            }
+            status if status.is_success() => {
+                // Sin soporte de rangos: se descarga entero
+                resume_from = 0;
+                let total = response.content_length().unwrap_or(size_hint);
+                break (response, total);
+            }
+            status => {
+                return Err(AudioInkError::ModelDownload(format!(
+                    "Error al descargar modelo: HTTP {}",
+                    status
+                )));
+            }
+        }
+
+        resume_from = 0;
+    };
+
+    // Mejor fallar ahora que a mitad de descarga
+    ensure_free_space(temp_path, total_size.saturating_sub(resume_from))?;
+
+    let mut file = if resume_from > 0 {
+        fs::OpenOptions::new().append(true).open(temp_path).await
+    } else {
+        fs::File::create(temp_path).await
+    }
+    .map_err(|e| AudioInkError::FileError(e.to_string()))?;
+
+    let mut downloaded = resume_from;
+    if let (Some(callback), true) = (on_progress, downloaded > 0) {
+        callback(downloaded as f32 / total_size as f32, downloaded, total_size);
+    }
+
+    let mut stream = response.bytes_stream();
+    loop {
+        // Una conexión parada no debe impedir cancelar
+        let next = tokio::select! {
+            next = stream.next() => next,
+            _ = cancel.cancelled() => return Err(AudioInkError::Cancelled),
+        };
+        let Some(chunk) = next else {
+            break;
+        };
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                // Lo recibido hasta aquí queda en disco para reanudar
+                let _ = file.flush().await;
+                return Err(AudioInkError::ModelDownload(e.to_string()));
+            }
+        };
 
         file.write_all(&chunk)
             .await
@@ -93,7 +518,7 @@ pub async fn download_model(
 
         downloaded += chunk.len() as u64;
 
-        if let Some(ref callback) = on_progress {
+        if let Some(callback) = on_progress {
             let progress = downloaded as f32 / total_size as f32;
             callback(progress, downloaded, total_size);
         }
@@ -103,23 +528,88 @@ pub async fn download_model(
         .await
         .map_err(|e| AudioInkError::FileError(e.to_string()))?;
 
-    // Renombrar archivo temporal al nombre final
-    fs::rename(&temp_path, &model_path)
+    Ok(())
+}
+
+/// Copia un modelo de un mirror local (`file://`) con el mismo progreso que una descarga
+async fn copy_local_file(
+    url: &str,
+    temp_path: &Path,
+    on_progress: Option<&DownloadProgressCallback>,
+    cancel: &CancellationToken,
+) -> AudioInkResult<()> {
+    let source = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.to_file_path().ok())
+        .ok_or_else(|| AudioInkError::ModelDownload(format!("URL no válida: {}", url)))?;
+
+    let mut reader = fs::File::open(&source)
+        .await
+        .map_err(|e| AudioInkError::ModelDownload(format!("{}: {}", source.display(), e)))?;
+    let total_size = reader.metadata().await?.len();
+    ensure_free_space(temp_path, total_size)?;
+    let mut file = fs::File::create(temp_path)
         .await
         .map_err(|e| AudioInkError::FileError(e.to_string()))?;
 
-    Ok(model_path)
+    let mut buffer = vec![0u8; 1024 * 1024];
+    let mut copied: u64 = 0;
+    loop {
+        cancel.check()?;
+        let n = reader.read(&mut buffer).await?;
+        if n == 0 {
+            break;
+        }
+        file.write_all(&buffer[..n])
+            .await
+            .map_err(|e| AudioInkError::FileError(e.to_string()))?;
+        copied += n as u64;
+
+        if let Some(callback) = on_progress {
+            callback(copied as f32 / total_size as f32, copied, total_size);
+        }
+    }
+
+    file.flush()
+        .await
+        .map_err(|e| AudioInkError::FileError(e.to_string()))?;
+    Ok(())
+}
+
+/// Lee `Content-Range: bytes 100-999/1000` (o `bytes */1000`)
+/// Devuelve el byte inicial y el tamaño total, si se conocen
+fn parse_content_range(value: &str) -> Option<(Option<u64>, Option<u64>)> {
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let start = match range.split_once('-') {
+        Some((start, _)) => Some(start.trim().parse().ok()?),
+        None if range == "*" => None,
+        None => return None,
+    };
+    Some((start, total.trim().parse().ok()))
 }
 
 /// Elimina un modelo descargado
+/// Los importados se quitan también del manifiesto
 pub async fn delete_model(model: &WhisperModel) -> AudioInkResult<()> {
+    if let WhisperModel::Custom(custom) = model {
+        remove_custom_model(&custom.name).await?;
+        return Ok(());
+    }
+
+    // Los directorios extra son de solo lectura
     let path = get_model_path(model);
+    if !path.starts_with(get_models_dir()) {
+        return Err(AudioInkError::FileError(format!(
+            "{} está en un directorio de solo lectura",
+            path.display()
+        )));
+    }
     if path.exists() {
         fs::remove_file(&path)
             .await
             .map_err(|e| AudioInkError::FileError(e.to_string()))?;
     }
-    Ok(())
+    forget_checksum(model.filename()).await
 }
 
 /// Obtiene información sobre el espacio usado por los modelos
@@ -128,7 +618,7 @@ pub async fn get_models_storage_info() -> AudioInkResult<ModelsStorageInfo> {
     let mut total_size: u64 = 0;
     let mut model_sizes: Vec<(WhisperModel, u64)> = Vec::new();
 
-    for model in WhisperModel::all() {
+    for model in list_all_models() {
         let path = get_model_path(&model);
         if path.exists() {
             if let Ok(metadata) = fs::metadata(&path).await {
@@ -162,7 +652,7 @@ impl ModelsStorageInfo {
 }
 
 /// Formatea bytes en formato legible (KB, MB, GB)
-fn format_bytes(bytes: u64) -> String {
+pub(crate) fn format_bytes(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
@@ -177,3 +667,260 @@ fn format_bytes(bytes: u64) -> String {
         format!("{} bytes", bytes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::net::TcpListener;
+
+    /// Cabecera Range de cada petición recibida (solo el byte inicial)
+    type SeenRanges = Arc<Mutex<Vec<Option<String>>>>;
+
+    /// Servidor HTTP mínimo que sirve `body` y anota la cabecera Range de cada petición
+    /// Con `cut_first_after`, la primera respuesta se corta tras ese número de bytes
+    async fn serve_stub(
+        body: Vec<u8>,
+        honor_ranges: bool,
+        cut_first_after: Option<usize>,
+    ) -> (String, SeenRanges) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/model.bin", listener.local_addr().unwrap());
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+
+        let seen = ranges.clone();
+        tokio::spawn(async move {
+            let mut cut = cut_first_after;
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8_lossy(&request).to_lowercase();
+                let range_start = request
+                    .lines()
+                    .find_map(|line| line.strip_prefix("range: bytes="))
+                    .map(|value| value.trim_end_matches('-').to_string());
+                seen.lock().unwrap().push(range_start.clone());
+
+                let start = match range_start {
+                    Some(start) if honor_ranges => start.parse::<usize>().unwrap(),
+                    _ => 0,
+                };
+                let head = if start > 0 {
+                    format!(
+                        "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\n\
+                         Content-Range: bytes {}-{}/{}\r\nConnection: close\r\n\r\n",
+                        body.len() - start,
+                        start,
+                        body.len() - 1,
+                        body.len()
+                    )
+                } else {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        body.len()
+                    )
+                };
+                socket.write_all(head.as_bytes()).await.unwrap();
+                let end = cut.take().map_or(body.len(), |n| start + n);
+                socket.write_all(&body[start..end]).await.unwrap();
+                socket.shutdown().await.unwrap();
+            }
+        });
+
+        (url, ranges)
+    }
+
+    fn test_body() -> Vec<u8> {
+        (0..10_000u32).map(|i| (i % 251) as u8).collect()
+    }
+
+    /// Pares (descargado, total) recibidos por el callback de progreso
+    type ProgressEvents = Arc<Mutex<Vec<(u64, u64)>>>;
+
+    fn recording_callback() -> (DownloadProgressCallback, ProgressEvents) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        let callback: DownloadProgressCallback = Box::new(move |_, downloaded, total| {
+            recorded.lock().unwrap().push((downloaded, total));
+        });
+        (callback, events)
+    }
+
+    #[tokio::test]
+    async fn test_download_resumes_after_dropped_connection() {
+        let body = test_body();
+        let (url, ranges) = serve_stub(body.clone(), true, Some(4_000)).await;
+        let dir = tempfile::tempdir().unwrap();
+        let temp_path = dir.path().join("model.downloading");
+        let cancel = CancellationToken::new();
+
+        // La conexión se corta: el parcial se conserva
+        assert!(download_file(&url, &temp_path, 0, None, &cancel).await.is_err());
+        assert_eq!(fs::metadata(&temp_path).await.unwrap().len(), 4_000);
+
+        let (callback, events) = recording_callback();
+        download_file(&url, &temp_path, 0, Some(&callback), &cancel).await.unwrap();
+        assert_eq!(fs::read(&temp_path).await.unwrap(), body);
+        assert_eq!(ranges.lock().unwrap()[1].as_deref(), Some("4000"));
+
+        // El progreso parte de lo ya descargado y termina en el total
+        let events = events.lock().unwrap();
+        assert_eq!(events.first(), Some(&(4_000, 10_000)));
+        assert_eq!(events.last(), Some(&(10_000, 10_000)));
+    }
+
+    #[tokio::test]
+    async fn test_download_restarts_when_server_ignores_range() {
+        let body = test_body();
+        let (url, ranges) = serve_stub(body.clone(), false, None).await;
+        let dir = tempfile::tempdir().unwrap();
+        let temp_path = dir.path().join("model.downloading");
+        let cancel = CancellationToken::new();
+        fs::write(&temp_path, &body[..3_000]).await.unwrap();
+
+        let (callback, events) = recording_callback();
+        download_file(&url, &temp_path, 0, Some(&callback), &cancel).await.unwrap();
+        assert_eq!(fs::read(&temp_path).await.unwrap(), body);
+        assert_eq!(ranges.lock().unwrap()[0].as_deref(), Some("3000"));
+        assert_eq!(events.lock().unwrap().last(), Some(&(10_000, 10_000)));
+    }
+
+    #[tokio::test]
+    async fn test_download_from_file_mirror() {
+        let body = test_body();
+        let mirror = tempfile::tempdir().unwrap();
+        let source = mirror.path().join("ggml-tiny.bin");
+        fs::write(&source, &body).await.unwrap();
+        let url = reqwest::Url::from_file_path(&source).unwrap().to_string();
+
+        let dir = tempfile::tempdir().unwrap();
+        let temp_path = dir.path().join("model.downloading");
+        let cancel = CancellationToken::new();
+        let (callback, events) = recording_callback();
+        download_file(&url, &temp_path, 0, Some(&callback), &cancel).await.unwrap();
+        assert_eq!(fs::read(&temp_path).await.unwrap(), body);
+        assert_eq!(events.lock().unwrap().last(), Some(&(10_000, 10_000)));
+
+        let missing = reqwest::Url::from_file_path(mirror.path().join("ggml-base.bin")).unwrap();
+        let result = download_file(missing.as_str(), &temp_path, 0, None, &cancel).await;
+        assert!(matches!(result, Err(AudioInkError::ModelDownload(_))));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stalled_download() {
+        // Envía la cabecera y unos bytes, y luego deja la conexión parada
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/model.bin", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            let head = "HTTP/1.1 200 OK\r\nContent-Length: 10000\r\n\r\n";
+            socket.write_all(head.as_bytes()).await.unwrap();
+            socket.write_all(&[0u8; 100]).await.unwrap();
+            tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let temp_path = dir.path().join("model.downloading");
+        let cancel = CancellationToken::new();
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            canceller.cancel();
+        });
+
+        let started = std::time::Instant::now();
+        let result = download_file(&url, &temp_path, 0, None, &cancel).await;
+        assert!(matches!(result, Err(AudioInkError::Cancelled)));
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_download_manager_dedupes_and_queues() {
+        let manager = Arc::new(DownloadManager::new());
+        let tiny = manager.enqueue(&WhisperModel::TINY).unwrap();
+        let tiny_again = manager.enqueue(&WhisperModel::TINY).unwrap();
+        let base = manager.enqueue(&WhisperModel::BASE).unwrap();
+        assert!(!tiny.joined);
+        assert!(tiny_again.joined);
+        assert_eq!(tiny_again.id, tiny.id);
+        assert_ne!(base.id, tiny.id);
+
+        let listed = manager.list().unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].model, "tiny");
+        assert!(listed.iter().all(|d| d.queued));
+
+        // Con el turno ocupado, ambos esperan en la cola hasta que se cancelan
+        let _busy = manager.slot.acquire().await.unwrap();
+        let (tiny_id, base_id) = (tiny.id.clone(), base.id.clone());
+        let waits = [tiny, tiny_again, base].map(|ticket| {
+            let manager = manager.clone();
+            tokio::spawn(async move { manager.wait(ticket, None).await })
+        });
+
+        assert!(manager.cancel(&base_id).unwrap());
+        assert!(manager.cancel(&tiny_id).unwrap());
+        assert!(!manager.cancel("unknown").unwrap());
+        for wait in waits {
+            assert!(matches!(wait.await.unwrap(), Err(AudioInkError::Cancelled)));
+        }
+        assert!(manager.list().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_download_manager_cancel_removes_partial() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = Arc::new(DownloadManager::new());
+        let tiny = manager.enqueue(&WhisperModel::TINY).unwrap();
+        let base = manager.enqueue(&WhisperModel::BASE).unwrap();
+
+        // Parciales de intentos anteriores, fuera del directorio de modelos real
+        let (tiny_partial, base_partial) =
+            (dir.path().join("tiny.downloading"), dir.path().join("base.downloading"));
+        {
+            let mut downloads = manager.lock().unwrap();
+            downloads[0].partial = tiny_partial.clone();
+            downloads[1].partial = base_partial.clone();
+            // Como si tiny ya estuviera escribiendo en su parcial
+            downloads[0].started = true;
+        }
+        fs::write(&tiny_partial, b"partial").await.unwrap();
+        fs::write(&base_partial, b"partial").await.unwrap();
+
+        let busy = manager.slot.acquire().await.unwrap();
+        let (tiny_id, base_id) = (tiny.id.clone(), base.id.clone());
+        let waits = [tiny, base].map(|ticket| {
+            let manager = manager.clone();
+            tokio::spawn(async move { manager.wait(ticket, None).await })
+        });
+
+        // En cola se borra en el acto; en curso, cuando la descarga termina
+        assert!(manager.cancel(&base_id).unwrap());
+        assert!(!base_partial.exists());
+        assert!(manager.cancel(&tiny_id).unwrap());
+        assert!(tiny_partial.exists());
+
+        drop(busy);
+        for wait in waits {
+            assert!(matches!(wait.await.unwrap(), Err(AudioInkError::Cancelled)));
+        }
+        assert!(!tiny_partial.exists());
+    }
+
+    #[test]
+    fn test_parse_content_range() {
+        assert_eq!(parse_content_range("bytes 100-999/1000"), Some((Some(100), Some(1000))));
+        assert_eq!(parse_content_range("bytes 100-999/*"), Some((Some(100), None)));
+        assert_eq!(parse_content_range("bytes */1000"), Some((None, Some(1000))));
+        assert_eq!(parse_content_range("items 0-1/2"), None);
+    }
+}
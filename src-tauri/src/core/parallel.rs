@@ -0,0 +1,126 @@
+use crate::utils::AudioInkResult;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+/// Ejecuta `job(i)` para cada `i` en `0..count` con hasta `workers` hilos y
+/// entrega los resultados a `on_ready` en orden
+///
+/// `on_ready` corre en el hilo llamante en cuanto el resultado `i` y todos los
+/// anteriores están listos. Tras el primer error (de un job o de `on_ready`) no
+/// se empiezan más jobs; se espera a los que están en curso y se devuelve ese error.
+pub fn run_ordered<T: Send>(
+    count: usize,
+    workers: usize,
+    job: impl Fn(usize) -> AudioInkResult<T> + Sync,
+    mut on_ready: impl FnMut(usize, T) -> AudioInkResult<()>,
+) -> AudioInkResult<()> {
+    let workers = workers.clamp(1, count.max(1));
+    let next_job = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+
+    std::thread::scope(|scope| {
+        let (tx, rx) = mpsc::channel();
+        for _ in 0..workers {
+            let tx = tx.clone();
+            let (job, next_job, stop) = (&job, &next_job, &stop);
+            scope.spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let i = next_job.fetch_add(1, Ordering::Relaxed);
+                    if i >= count {
+                        break;
+                    }
+                    let result = job(i);
+                    if result.is_err() {
+                        stop.store(true, Ordering::Relaxed);
+                    }
+                    if tx.send((i, result)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        // Los resultados llegan en cualquier orden; se guardan hasta que toca entregarlos
+        let mut pending = BTreeMap::new();
+        let mut next_ready = 0;
+        let mut first_error = None;
+
+        for (i, result) in rx {
+            if first_error.is_some() {
+                continue;
+            }
+            match result {
+                Ok(value) => {
+                    pending.insert(i, value);
+                    while let Some(value) = pending.remove(&next_ready) {
+                        if let Err(e) = on_ready(next_ready, value) {
+                            stop.store(true, Ordering::Relaxed);
+                            first_error = Some(e);
+                            break;
+                        }
+                        next_ready += 1;
+                    }
+                }
+                Err(e) => first_error = Some(e),
+            }
+        }
+
+        first_error.map_or(Ok(()), Err)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::AudioInkError;
+    use std::time::Duration;
+
+    #[test]
+    fn test_run_ordered_delivers_in_order() {
+        let mut delivered = Vec::new();
+        run_ordered(
+            6,
+            3,
+            |i| {
+                // Los primeros jobs terminan los últimos
+                std::thread::sleep(Duration::from_millis(((6 - i) * 5) as u64));
+                Ok(i * 10)
+            },
+            |i, value| {
+                delivered.push((i, value));
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(delivered, (0..6).map(|i| (i, i * 10)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_run_ordered_stops_on_error() {
+        let started = AtomicUsize::new(0);
+        let mut delivered = Vec::new();
+        let result = run_ordered(
+            100,
+            2,
+            |i| {
+                started.fetch_add(1, Ordering::Relaxed);
+                if i == 3 {
+                    Err(AudioInkError::Whisper("chunk 3".to_string()))
+                } else {
+                    Ok(i)
+                }
+            },
+            |i, _| {
+                delivered.push(i);
+                Ok(())
+            },
+        );
+
+        assert!(matches!(result, Err(AudioInkError::Whisper(_))));
+        assert!(started.load(Ordering::Relaxed) < 100);
+        assert!(delivered.iter().all(|&i| i < 3));
+    }
+}
@@ -3,8 +3,10 @@
 //! This module provides functions to:
 //! - Accelerate audio files using ffmpeg's atempo filter
 //! - Extract audio from video files (mp4, avi, mov)
+//!
 //! Maximum recommended speed is 2.0x to maintain transcription quality.
 
+use crate::core::{run_command_cancellable, CancellationToken};
 use crate::models::VIDEO_FORMATS;
 use crate::utils::{get_ffmpeg_install_instructions, AudioInkError, AudioInkResult};
 use std::path::{Path, PathBuf};
@@ -20,17 +22,13 @@ const FFMPEG_PATHS: &[&str] = &[
 
 /// Find the ffmpeg binary path
 fn find_ffmpeg() -> Option<&'static str> {
-    for path in FFMPEG_PATHS {
-        if Command::new(path)
+    FFMPEG_PATHS.iter().copied().find(|path| {
+        Command::new(path)
             .arg("-version")
             .output()
             .map(|o| o.status.success())
             .unwrap_or(false)
-        {
-            return Some(path);
-        }
-    }
-    None
+    })
 }
 
 /// Check if ffmpeg is available in the system
@@ -43,15 +41,20 @@ pub fn is_ffmpeg_available() -> bool {
 /// # Arguments
 /// * `input_path` - Path to the input audio file
 /// * `speed` - Speed factor (1.0 = normal, 2.0 = 2x faster). Max recommended: 2.0
+/// * `cancel` - Token that kills ffmpeg and removes the partial output when cancelled
 ///
 /// # Returns
 /// * `PathBuf` - Path to the sped-up temporary audio file
 ///
 /// # Note
 /// The caller is responsible for cleaning up the temporary file after use
-pub fn apply_audio_speedup(input_path: &Path, speed: f32) -> AudioInkResult<PathBuf> {
+pub fn apply_audio_speedup(
+    input_path: &Path,
+    speed: f32,
+    cancel: &CancellationToken,
+) -> AudioInkResult<PathBuf> {
     // Validate speed range (atempo filter supports 0.5 to 2.0)
-    if speed < 0.5 || speed > 2.0 {
+    if !(0.5..=2.0).contains(&speed) {
         return Err(AudioInkError::Internal(format!(
             "Speed must be between 0.5 and 2.0, got: {}",
             speed
@@ -76,18 +79,22 @@ pub fn apply_audio_speedup(input_path: &Path, speed: f32) -> AudioInkResult<Path
 
     // Build ffmpeg command
     // ffmpeg -i input.wav -filter:a "atempo=1.5" -vn output.wav
-    let output = Command::new(ffmpeg)
+    let mut command = Command::new(ffmpeg);
+    command
         .arg("-i")
         .arg(input_path)
         .arg("-filter:a")
         .arg(format!("atempo={}", speed))
         .arg("-vn") // No video
         .arg("-y") // Overwrite output
-        .arg(&output_path)
-        .output()
-        .map_err(|e| AudioInkError::Internal(format!("Failed to run ffmpeg: {}", e)))?;
+        .arg(&output_path);
+
+    let output = run_command_cancellable(&mut command, "ffmpeg", cancel).inspect_err(|_| {
+        let _ = std::fs::remove_file(&output_path);
+    })?;
 
     if !output.status.success() {
+        let _ = std::fs::remove_file(&output_path);
         let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(AudioInkError::Internal(format!(
             "ffmpeg speedup failed: {}",
@@ -125,13 +132,17 @@ pub fn is_video_format(path: &Path) -> bool {
 ///
 /// # Arguments
 /// * `input_path` - Path to the input video file (mp4, avi, mov, etc.)
+/// * `cancel` - Token that kills ffmpeg and removes the partial output when cancelled
 ///
 /// # Returns
 /// * `PathBuf` - Path to the extracted audio file (wav format)
 ///
 /// # Note
 /// The caller is responsible for cleaning up the temporary file after use
-pub fn extract_audio_from_video(input_path: &Path) -> AudioInkResult<PathBuf> {
+pub fn extract_audio_from_video(
+    input_path: &Path,
+    cancel: &CancellationToken,
+) -> AudioInkResult<PathBuf> {
     // Check ffmpeg availability
     let ffmpeg = find_ffmpeg().ok_or_else(|| {
         AudioInkError::Internal(get_ffmpeg_install_instructions().to_string())
@@ -145,7 +156,8 @@ pub fn extract_audio_from_video(input_path: &Path) -> AudioInkResult<PathBuf> {
 
     // Build ffmpeg command to extract audio
     // ffmpeg -i input.mp4 -vn -acodec pcm_s16le -ar 16000 -ac 1 output.wav
-    let output = Command::new(ffmpeg)
+    let mut command = Command::new(ffmpeg);
+    command
         .arg("-i")
         .arg(input_path)
         .arg("-vn") // No video
@@ -156,11 +168,14 @@ pub fn extract_audio_from_video(input_path: &Path) -> AudioInkResult<PathBuf> {
         .arg("-ac")
         .arg("1") // Mono
         .arg("-y") // Overwrite output
-        .arg(&output_path)
-        .output()
-        .map_err(|e| AudioInkError::Internal(format!("Failed to run ffmpeg: {}", e)))?;
+        .arg(&output_path);
+
+    let output = run_command_cancellable(&mut command, "ffmpeg", cancel).inspect_err(|_| {
+        let _ = std::fs::remove_file(&output_path);
+    })?;
 
     if !output.status.success() {
+        let _ = std::fs::remove_file(&output_path);
         let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(AudioInkError::Internal(format!(
             "ffmpeg audio extraction failed: {}",
@@ -204,11 +219,12 @@ mod tests {
     #[test]
     fn test_speed_validation() {
         // Speed too low
-        let result = apply_audio_speedup(Path::new("/tmp/test.wav"), 0.4);
+        let cancel = CancellationToken::new();
+        let result = apply_audio_speedup(Path::new("/tmp/test.wav"), 0.4, &cancel);
         assert!(result.is_err());
 
         // Speed too high
-        let result = apply_audio_speedup(Path::new("/tmp/test.wav"), 2.5);
+        let result = apply_audio_speedup(Path::new("/tmp/test.wav"), 2.5, &cancel);
         assert!(result.is_err());
     }
 }
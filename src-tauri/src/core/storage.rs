@@ -0,0 +1,124 @@
+//! Espacio en disco: comprobación previa a las descargas y uso de archivos temporales
+
+use crate::core::{format_bytes, get_models_dir};
+use crate::utils::{AudioInkError, AudioInkResult};
+use std::path::Path;
+use tokio::fs;
+
+/// Prefijo de los archivos temporales del pipeline (speedup, audio extraído, subidas al API)
+const TEMP_FILE_PREFIX: &str = "audioink_";
+
+/// Espacio libre en el disco de `path`
+/// Si la ruta aún no existe, se mira el primer directorio que sí exista
+pub fn available_space(path: &Path) -> AudioInkResult<u64> {
+    let existing = path
+        .ancestors()
+        .find(|p| p.exists())
+        .ok_or_else(|| AudioInkError::FileError(format!("Ruta no válida: {}", path.display())))?;
+    fs2::available_space(existing).map_err(|e| AudioInkError::FileError(e.to_string()))
+}
+
+/// Comprueba que caben `needed` bytes en el disco de `path`
+pub fn ensure_free_space(path: &Path, needed: u64) -> AudioInkResult<()> {
+    let available = available_space(path)?;
+    if available < needed {
+        return Err(AudioInkError::InsufficientDiskSpace(format!(
+            "se necesitan {} y hay {} libres en {}",
+            format_bytes(needed),
+            format_bytes(available),
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Tamaño total de los archivos bajo `path` (0 si no existe)
+pub async fn dir_size(path: &Path) -> u64 {
+    let mut total = 0;
+    let mut pending = vec![path.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(mut entries) = fs::read_dir(&dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Ok(metadata) = entry.metadata().await else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else {
+                total += metadata.len();
+            }
+        }
+    }
+
+    total
+}
+
+/// Espacio que ocupan los temporales de AudioInk en el directorio temporal del sistema
+pub async fn temp_files_size() -> u64 {
+    temp_files_size_in(&std::env::temp_dir()).await
+}
+
+/// Espacio que ocupan las descargas de modelos sin terminar (`.downloading`)
+pub async fn partial_downloads_size() -> u64 {
+    entries_size(&get_models_dir(), |name| name.ends_with(".downloading")).await
+}
+
+/// audioink_youtube es un directorio con los audios descargados de YouTube
+async fn temp_files_size_in(temp_dir: &Path) -> u64 {
+    entries_size(temp_dir, |name| name.starts_with(TEMP_FILE_PREFIX)).await
+}
+
+/// Tamaño de las entradas de `dir` cuyo nombre cumple `matches`, con los directorios completos
+async fn entries_size(dir: &Path, matches: impl Fn(&str) -> bool) -> u64 {
+    let mut total = 0;
+    let Ok(mut entries) = fs::read_dir(dir).await else {
+        return 0;
+    };
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if !matches(&entry.file_name().to_string_lossy()) {
+            continue;
+        }
+        match entry.metadata().await {
+            Ok(metadata) if metadata.is_dir() => total += dir_size(&entry.path()).await,
+            Ok(metadata) => total += metadata.len(),
+            Err(_) => {}
+        }
+    }
+
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ensure_free_space() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(ensure_free_space(dir.path(), 1).is_ok());
+        // Rutas que aún no existen se miden en su directorio padre
+        assert!(ensure_free_space(&dir.path().join("models/ggml-base.bin"), 1).is_ok());
+
+        let result = ensure_free_space(dir.path(), u64::MAX);
+        assert!(matches!(result, Err(AudioInkError::InsufficientDiskSpace(_))));
+    }
+
+    #[tokio::test]
+    async fn test_temp_files_size_counts_only_audioink_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("audioink_speedup_1.wav"), [0u8; 100]).await.unwrap();
+        fs::write(dir.path().join("other_app.tmp"), [0u8; 1000]).await.unwrap();
+        let youtube = dir.path().join("audioink_youtube");
+        fs::create_dir_all(youtube.join("nested")).await.unwrap();
+        fs::write(youtube.join("video.m4a"), [0u8; 20]).await.unwrap();
+        fs::write(youtube.join("nested/part.m4a"), [0u8; 3]).await.unwrap();
+
+        assert_eq!(temp_files_size_in(dir.path()).await, 123);
+        assert_eq!(dir_size(&youtube).await, 23);
+        assert_eq!(dir_size(&dir.path().join("missing")).await, 0);
+    }
+}
@@ -1,9 +1,72 @@
-use crate::core::{get_model_path, is_model_downloaded, split_into_chunks, needs_chunking};
-use crate::models::{Language, TranscriptionResult, WhisperModel, AudioInfo};
+use crate::core::{
+    compact_speech, detect_speech_regions, filter_hallucinations, get_model_path,
+    is_model_downloaded, needs_chunking, run_ordered, split_into_chunks_with, AudioChunk,
+    CancellationToken, ChunkConfig, TimelineMap, VadConfig,
+};
+use crate::models::{
+    dominant_language, language_spans, render_plain_text, render_segments, AudioInfo,
+    DecodingOptions, DecodingStrategy, HallucinationFilter, Language, LanguageProbability,
+    Segment, Task, TranscriptionResult, WhisperModel, Word, CHUNK_DURATION_SECS,
+    WHISPER_SAMPLE_RATE,
+};
 use crate::utils::{AudioInkError, AudioInkResult};
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Instant;
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState};
+
+/// Callback de progreso: recibe (progress, message, chunk_text)
+pub type ProgressCallback = Box<dyn Fn(f32, String, Option<String>) + Send + Sync>;
+
+/// Opciones del motor para una transcripción
+#[derive(Debug, Clone, Default)]
+pub struct EngineOptions {
+    /// Idioma del audio (Auto = detección automática)
+    pub language: Language,
+    /// Renderizar el texto con marcas de tiempo `[HH:MM:SS]`
+    pub include_timestamps: bool,
+    /// Calcular tiempos y probabilidad por palabra a partir de los tokens
+    pub word_timestamps: bool,
+    /// Transcribir o traducir al inglés
+    pub task: Task,
+    /// Transcribir solo las regiones con voz (detección por energía)
+    pub vad: bool,
+    /// Segundos de audio repetidos entre chunks consecutivos (0 = sin solape)
+    pub chunk_overlap_secs: f32,
+    /// Prompt inicial o glosario (nombres propios, jerga del dominio)
+    pub initial_prompt: Option<String>,
+    /// Con idioma automático, detectar el idioma de cada chunk por separado
+    pub language_per_chunk: bool,
+    /// Estrategia de muestreo, temperatura y umbrales del decodificador
+    pub decoding: DecodingOptions,
+    /// Eliminar o marcar bucles y texto inventado sobre silencio
+    pub hallucination_filter: HallucinationFilter,
+    /// Chunks que se transcriben a la vez, cada uno con su estado (0 o 1 = en serie)
+    pub chunk_workers: usize,
+    /// Hilos de Whisper por transcripción (0 = automático)
+    pub n_threads: usize,
+}
+
+impl EngineOptions {
+    /// Detección de idioma por chunk activa (solo tiene sentido con idioma automático)
+    fn detects_language_per_chunk(&self) -> bool {
+        self.language_per_chunk && self.language == Language::Auto
+    }
+
+    /// Copia de las opciones con el idioma fijado a `code` (si es un código conocido)
+    fn with_language(&self, code: &str) -> EngineOptions {
+        EngineOptions {
+            language: Language::from_code(code).unwrap_or(self.language),
+            ..self.clone()
+        }
+    }
+}
+
+/// Número de idiomas candidatos que se guardan con el resultado
+pub const LANGUAGE_CANDIDATES: usize = 5;
+
+/// Duración de los chunks con detección de idioma por chunk (una ventana de Whisper)
+const LANGUAGE_CHUNK_SECS: f32 = 30.0;
 
 /// Motor de transcripción con Whisper
 pub struct WhisperEngine {
@@ -21,14 +84,7 @@ impl WhisperEngine {
             )));
         }
 
-        let model_path = get_model_path(model);
-        let params = WhisperContextParameters::default();
-
-        let context = WhisperContext::new_with_params(
-            model_path.to_str().unwrap(),
-            params,
-        )
-        .map_err(|e| AudioInkError::Whisper(format!("Error al cargar modelo: {}", e)))?;
+        let context = load_context(&get_model_path(model))?;
 
         Ok(Self {
             context,
@@ -36,13 +92,18 @@ impl WhisperEngine {
         })
     }
 
+    /// Comprueba que whisper.cpp puede cargar el archivo como modelo
+    pub fn validate_model_file(path: &Path) -> AudioInkResult<()> {
+        load_context(path).map(|_| ())
+    }
+
     /// Transcribe audio (samples f32 mono 16kHz)
     pub fn transcribe(
         &self,
         samples: &[f32],
         language: &Language,
         audio_info: Option<AudioInfo>,
-        on_progress: Option<Box<dyn Fn(f32, String, Option<String>) + Send + Sync>>,
+        on_progress: Option<ProgressCallback>,
     ) -> AudioInkResult<TranscriptionResult> {
         self.transcribe_with_timestamps(samples, language, audio_info, on_progress, false)
     }
@@ -54,19 +115,73 @@ impl WhisperEngine {
         samples: &[f32],
         language: &Language,
         audio_info: Option<AudioInfo>,
-        on_progress: Option<Box<dyn Fn(f32, String, Option<String>) + Send + Sync>>,
+        on_progress: Option<ProgressCallback>,
         include_timestamps: bool,
+    ) -> AudioInkResult<TranscriptionResult> {
+        let options = EngineOptions {
+            language: *language,
+            include_timestamps,
+            ..Default::default()
+        };
+        self.transcribe_with_options(samples, &options, audio_info, on_progress, &CancellationToken::new())
+    }
+
+    /// Transcribe audio con las opciones completas del motor
+    /// Devuelve `AudioInkError::Cancelled` si `cancel` se activa durante la inferencia
+    pub fn transcribe_with_options(
+        &self,
+        samples: &[f32],
+        options: &EngineOptions,
+        audio_info: Option<AudioInfo>,
+        on_progress: Option<ProgressCallback>,
+        cancel: &CancellationToken,
     ) -> AudioInkResult<TranscriptionResult> {
         let start_time = Instant::now();
 
+        // Quitar los silencios; los tiempos se devuelven a la línea de tiempo original
+        let compacted;
+        let (samples, timeline) = if options.vad {
+            let regions = detect_speech_regions(samples, &VadConfig::default());
+            if regions.is_empty() {
+                if let Some(ref callback) = on_progress {
+                    callback(1.0, "No speech detected".to_string(), None);
+                }
+                return Ok(TranscriptionResult {
+                    text: String::new(),
+                    language: None,
+                    audio_info,
+                    processing_time: start_time.elapsed().as_secs_f64(),
+                    segments: Vec::new(),
+                    task: options.task,
+                    language_probabilities: Vec::new(),
+                    language_spans: Vec::new(),
+                    hallucinations: None,
+                });
+            }
+            compacted = compact_speech(samples, &regions);
+            (compacted.samples.as_slice(), Some(&compacted.timeline))
+        } else {
+            (samples, None)
+        };
+
         // Verificar si necesita procesamiento en chunks
-        if needs_chunking(samples) {
-            return self.transcribe_chunked_with_timestamps(samples, language, audio_info, on_progress, include_timestamps);
+        let language_chunk_len = (LANGUAGE_CHUNK_SECS * WHISPER_SAMPLE_RATE as f32) as usize;
+        let per_chunk_language = options.detects_language_per_chunk();
+        if needs_chunking(samples) || (per_chunk_language && samples.len() > language_chunk_len) {
+            return self.transcribe_chunked_with_timestamps(samples, options, timeline, audio_info, on_progress, cancel);
         }
 
         // Transcripción directa para archivos cortos (no chunked, so no progressive callback needed)
-        let text = self.transcribe_segment_with_options(samples, language, None, include_timestamps, 0)?;
-        let detected_language = self.detect_language_from_samples(samples)?;
+        let (detected_language, language_probabilities) = self.resolve_language(samples, options, cancel)?;
+        let options = &options.with_language(&detected_language);
+        let prompt = build_chunk_prompt(options.initial_prompt.as_deref(), "");
+        let mut segments = self.transcribe_segment_with_options(samples, options, 0, prompt.as_deref(), cancel)?;
+        remap_segments(&mut segments, timeline);
+        if per_chunk_language {
+            tag_language(&mut segments, &options.language);
+        }
+        let hallucinations = filter_hallucinations(&mut segments, &options.hallucination_filter);
+        let text = render_segments(&segments, options.include_timestamps);
 
         // Emit the complete text for short files
         if let Some(ref callback) = on_progress {
@@ -74,12 +189,18 @@ impl WhisperEngine {
         }
 
         let processing_time = start_time.elapsed().as_secs_f64();
+        let spans = language_spans(&segments);
 
         Ok(TranscriptionResult {
             text,
             language: Some(detected_language),
             audio_info,
             processing_time,
+            segments,
+            task: options.task,
+            language_probabilities,
+            language_spans: spans,
+            hallucinations,
         })
     }
 
@@ -87,30 +208,90 @@ impl WhisperEngine {
     fn transcribe_chunked_with_timestamps(
         &self,
         samples: &[f32],
-        language: &Language,
+        options: &EngineOptions,
+        timeline: Option<&TimelineMap>,
         audio_info: Option<AudioInfo>,
-        on_progress: Option<Box<dyn Fn(f32, String, Option<String>) + Send + Sync>>,
-        include_timestamps: bool,
+        on_progress: Option<ProgressCallback>,
+        cancel: &CancellationToken,
     ) -> AudioInkResult<TranscriptionResult> {
-        use crate::models::CHUNK_DURATION_SECS;
-
         let start_time = Instant::now();
-        let chunks = split_into_chunks(samples);
-        let total_chunks = chunks.len();
-        let mut transcriptions: Vec<String> = Vec::new();
+        let per_chunk_language = options.detects_language_per_chunk();
+        let chunk_config = ChunkConfig {
+            chunk_secs: if per_chunk_language { LANGUAGE_CHUNK_SECS } else { CHUNK_DURATION_SECS },
+            overlap_secs: options.chunk_overlap_secs,
+            ..ChunkConfig::default()
+        };
+        let chunks = split_into_chunks_with(samples, &chunk_config);
 
         // Detectar idioma en el primer chunk
-        let detected_language = if !chunks.is_empty() {
-            self.detect_language_from_samples(&chunks[0])?
+        let (detected_language, language_probabilities) = match chunks.first() {
+            Some(chunk) => self.resolve_language(chunk.samples, options, cancel)?,
+            None => ("unknown".to_string(), Vec::new()),
+        };
+        let file_options = options.with_language(&detected_language);
+
+        let workers = options.chunk_workers.min(chunks.len());
+        let mut segments = if workers > 1 {
+            self.transcribe_chunks_parallel(
+                &chunks,
+                options,
+                &file_options,
+                workers,
+                timeline,
+                on_progress.as_ref(),
+                cancel,
+            )?
         } else {
-            "unknown".to_string()
+            self.transcribe_chunks_in_order(
+                &chunks,
+                options,
+                &file_options,
+                timeline,
+                on_progress.as_ref(),
+                cancel,
+            )?
         };
 
-        // Calculate chunk duration in ms for offset
-        let chunk_duration_ms = (CHUNK_DURATION_SECS * 1000.0) as i64;
+        let hallucinations = filter_hallucinations(&mut segments, &options.hallucination_filter);
+        let full_text = render_segments(&segments, options.include_timestamps);
+        let processing_time = start_time.elapsed().as_secs_f64();
+
+        // En el modo por chunk el idioma del archivo es el predominante
+        let spans = language_spans(&segments);
+        let language = dominant_language(&spans).unwrap_or(detected_language);
+
+        Ok(TranscriptionResult {
+            text: full_text,
+            language: Some(language),
+            audio_info,
+            processing_time,
+            segments,
+            task: options.task,
+            language_probabilities,
+            language_spans: spans,
+            hallucinations,
+        })
+    }
+
+    /// Transcribe los chunks uno tras otro
+    /// Cada chunk recibe el final del anterior como prompt
+    fn transcribe_chunks_in_order(
+        &self,
+        chunks: &[AudioChunk],
+        options: &EngineOptions,
+        file_options: &EngineOptions,
+        timeline: Option<&TimelineMap>,
+        on_progress: Option<&ProgressCallback>,
+        cancel: &CancellationToken,
+    ) -> AudioInkResult<Vec<Segment>> {
+        let per_chunk_language = options.detects_language_per_chunk();
+        let total_chunks = chunks.len();
+        let mut segments: Vec<Segment> = Vec::new();
 
         for (i, chunk) in chunks.iter().enumerate() {
-            if let Some(ref callback) = on_progress {
+            cancel.check()?;
+
+            if let Some(callback) = on_progress {
                 let progress = (i as f32 + 0.5) / total_chunks as f32;
                 callback(
                     progress,
@@ -119,12 +300,40 @@ impl WhisperEngine {
                 );
             }
 
-            let time_offset_ms = (i as i64) * chunk_duration_ms;
-            let text = self.transcribe_segment_with_options(chunk, language, None, include_timestamps, time_offset_ms)?;
-            transcriptions.push(text.clone());
+            // Cada chunk se transcribe en su propio idioma en el modo por chunk
+            let chunk_options = if per_chunk_language && i > 0 {
+                let (code, _) = self.resolve_language(chunk.samples, options, cancel)?;
+                options.with_language(&code)
+            } else {
+                file_options.clone()
+            };
+
+            // El final del chunk anterior da continuidad a nombres y estilo,
+            // salvo que esté en otro idioma
+            let previous = &segments[segments.len().saturating_sub(5)..];
+            let same_language = !per_chunk_language
+                || previous.last().and_then(|s| s.language.as_deref()) == chunk_options.language.code();
+            let previous_text = if same_language { render_plain_text(previous) } else { String::new() };
+            let prompt = build_chunk_prompt(options.initial_prompt.as_deref(), &previous_text);
+            let mut chunk_segments = self.transcribe_segment_with_options(
+                chunk.samples,
+                &chunk_options,
+                chunk.offset_ms(),
+                prompt.as_deref(),
+                cancel,
+            )?;
+            if chunk.overlap_samples > 0 {
+                dedupe_overlap(&segments, &mut chunk_segments, chunk.new_audio_start_ms());
+            }
+            remap_segments(&mut chunk_segments, timeline);
+            if per_chunk_language {
+                tag_language(&mut chunk_segments, &chunk_options.language);
+            }
+            let text = render_segments(&chunk_segments, options.include_timestamps);
+            segments.extend(chunk_segments);
 
             // Emit progress with the chunk text for progressive display
-            if let Some(ref callback) = on_progress {
+            if let Some(callback) = on_progress {
                 let progress = (i + 1) as f32 / total_chunks as f32;
                 callback(
                     progress,
@@ -134,31 +343,108 @@ impl WhisperEngine {
             }
         }
 
-        let separator = if include_timestamps { "\n" } else { " " };
-        let full_text = transcriptions.join(separator);
-        let processing_time = start_time.elapsed().as_secs_f64();
+        Ok(segments)
+    }
 
-        Ok(TranscriptionResult {
-            text: full_text,
-            language: Some(detected_language),
-            audio_info,
-            processing_time,
-        })
+    /// Transcribe los chunks con `workers` estados de Whisper a la vez y los
+    /// reensambla en orden
+    ///
+    /// Los chunks solo reciben el prompt del usuario: el texto del chunk
+    /// anterior aún no existe cuando empiezan.
+    #[allow(clippy::too_many_arguments)]
+    fn transcribe_chunks_parallel(
+        &self,
+        chunks: &[AudioChunk],
+        options: &EngineOptions,
+        file_options: &EngineOptions,
+        workers: usize,
+        timeline: Option<&TimelineMap>,
+        on_progress: Option<&ProgressCallback>,
+        cancel: &CancellationToken,
+    ) -> AudioInkResult<Vec<Segment>> {
+        let per_chunk_language = options.detects_language_per_chunk();
+        let total_chunks = chunks.len();
+        let n_threads = threads_per_worker(options.n_threads, workers);
+        let prompt = build_chunk_prompt(options.initial_prompt.as_deref(), "");
+        let mut segments: Vec<Segment> = Vec::new();
+
+        if let Some(callback) = on_progress {
+            callback(
+                0.0,
+                format!("Transcribing {} chunks with {} workers", total_chunks, workers),
+                None,
+            );
+        }
+
+        run_ordered(
+            total_chunks,
+            workers,
+            |i| {
+                let chunk = &chunks[i];
+                let chunk_options = if per_chunk_language && i > 0 {
+                    let (code, _) = self.resolve_language(chunk.samples, options, cancel)?;
+                    options.with_language(&code)
+                } else {
+                    file_options.clone()
+                };
+                let chunk_options = EngineOptions {
+                    n_threads,
+                    ..chunk_options
+                };
+
+                let mut chunk_segments = self.transcribe_segment_with_options(
+                    chunk.samples,
+                    &chunk_options,
+                    chunk.offset_ms(),
+                    prompt.as_deref(),
+                    cancel,
+                )?;
+                if per_chunk_language {
+                    tag_language(&mut chunk_segments, &chunk_options.language);
+                }
+                Ok(chunk_segments)
+            },
+            |i, mut chunk_segments| {
+                let chunk = &chunks[i];
+                if chunk.overlap_samples > 0 {
+                    dedupe_overlap(&segments, &mut chunk_segments, chunk.new_audio_start_ms());
+                }
+                remap_segments(&mut chunk_segments, timeline);
+                let text = render_segments(&chunk_segments, options.include_timestamps);
+                segments.extend(chunk_segments);
+
+                if let Some(callback) = on_progress {
+                    let progress = (i + 1) as f32 / total_chunks as f32;
+                    callback(
+                        progress,
+                        format!("Chunk {} of {} completed", i + 1, total_chunks),
+                        Some(text),
+                    );
+                }
+                Ok(())
+            },
+        )?;
+
+        Ok(segments)
     }
 
     /// Transcribe un segmento de audio con opciones
+    /// Los tiempos de los segmentos devueltos incluyen `time_offset_ms`
     fn transcribe_segment_with_options(
         &self,
         samples: &[f32],
-        language: &Language,
-        _on_progress: Option<&Box<dyn Fn(f32, String) + Send + Sync>>,
-        include_timestamps: bool,
+        options: &EngineOptions,
         time_offset_ms: i64,
-    ) -> AudioInkResult<String> {
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        prompt: Option<&str>,
+        cancel: &CancellationToken,
+    ) -> AudioInkResult<Vec<Segment>> {
+        let mut params = decoding_params(&options.decoding);
+        if options.n_threads > 0 {
+            params.set_n_threads(options.n_threads as i32);
+        }
 
         // Configurar idioma
-        if let Some(lang_code) = language.code() {
+        if let Some(lang_code) = options.language.code() {
             params.set_language(Some(lang_code));
         } else {
             params.set_language(None); // Auto-detect
@@ -167,21 +453,19 @@ impl WhisperEngine {
         params.set_print_progress(false);
         params.set_print_realtime(false);
         params.set_print_timestamps(false);
+        params.set_token_timestamps(options.word_timestamps);
+        params.set_translate(options.task.is_translate());
+        if let Some(prompt) = prompt {
+            params.set_initial_prompt(prompt);
+        }
 
         // Crear estado y ejecutar transcripción
-        let mut state = self
-            .context
-            .create_state()
-            .map_err(|e| AudioInkError::Whisper(e.to_string()))?;
-
-        state
-            .full(params, samples)
-            .map_err(|e| AudioInkError::Whisper(e.to_string()))?;
+        let state = self.run_full(params, samples, cancel)?;
 
-        // Extraer texto de los segmentos
+        // Extraer los segmentos con sus tiempos
         let num_segments = state.full_n_segments();
 
-        let mut text = String::new();
+        let mut segments = Vec::with_capacity(num_segments.max(0) as usize);
         for i in 0..num_segments {
             let segment = state
                 .get_segment(i)
@@ -191,29 +475,64 @@ impl WhisperEngine {
                 .to_str()
                 .map_err(|e| AudioInkError::Whisper(e.to_string()))?;
 
-            if include_timestamps {
-                // Get segment start time in centiseconds (10ms units)
-                let t0 = segment.start_timestamp();
-                // Convert to milliseconds and add offset
-                let start_ms = (t0 * 10) + time_offset_ms;
-                let timestamp = format_timestamp_ms(start_ms);
-                text.push_str(&format!("[{}] {}\n", timestamp, segment_text.trim()));
-            } else {
-                text.push_str(segment_text);
+            // Whisper reporta los tiempos en centisegundos (unidades de 10ms)
+            let start_ms = segment.start_timestamp() * 10 + time_offset_ms;
+            let end_ms = segment.end_timestamp() * 10 + time_offset_ms;
+
+            // Los tokens dan las palabras (si se piden) y la confianza media del segmento
+            let mut tokens = Vec::new();
+            let mut logprob_sum = 0.0;
+            let mut text_tokens = 0;
+            for t in 0..segment.n_tokens() {
+                let Some(token) = segment.get_token(t) else {
+                    continue;
+                };
+                let bytes = token
+                    .to_bytes()
+                    .map_err(|e| AudioInkError::Whisper(e.to_string()))?;
+                let data = token.token_data();
+                if !is_special_token(bytes) {
+                    logprob_sum += data.plog;
+                    text_tokens += 1;
+                }
+                if options.word_timestamps {
+                    tokens.push(TokenTiming {
+                        bytes: bytes.to_vec(),
+                        start_ms: data.t0 * 10 + time_offset_ms,
+                        end_ms: data.t1 * 10 + time_offset_ms,
+                        probability: data.p,
+                    });
+                }
             }
+            let avg_logprob = (text_tokens > 0).then(|| logprob_sum / text_tokens as f32);
+            let words = group_tokens_into_words(&tokens);
+
+            segments.push(Segment {
+                start_ms,
+                end_ms,
+                text: segment_text.trim().to_string(),
+                no_speech_prob: Some(segment.no_speech_probability()),
+                speaker_turn_next: segment.next_segment_speaker_turn(),
+                words,
+                language: None,
+                avg_logprob,
+                hallucination: None,
+            });
         }
 
-        Ok(text.trim().to_string())
+        Ok(segments)
     }
 
-    /// Detecta el idioma de un audio
-    pub fn detect_language(&self, samples: &[f32]) -> AudioInkResult<String> {
-        self.detect_language_from_samples(samples)
-    }
+    /// Detecta el idioma del audio y devuelve los `top_n` más probables
+    pub fn detect_language(
+        &self,
+        samples: &[f32],
+        top_n: usize,
+        cancel: &CancellationToken,
+    ) -> AudioInkResult<Vec<LanguageProbability>> {
+        cancel.check()?;
 
-    /// Detecta el idioma usando los primeros 30 segundos de audio
-    fn detect_language_from_samples(&self, samples: &[f32]) -> AudioInkResult<String> {
-        // Usar máximo 30 segundos para detección
+        // Whisper solo mira la primera ventana de 30 segundos
         let sample_size = (30.0 * 16000.0) as usize;
         let sample = if samples.len() > sample_size {
             &samples[..sample_size]
@@ -221,21 +540,66 @@ impl WhisperEngine {
             samples
         };
 
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-        params.set_language(None); // Auto-detect
-
+        let threads = detection_threads();
         let mut state = self
             .context
             .create_state()
             .map_err(|e| AudioInkError::Whisper(e.to_string()))?;
-
         state
-            .full(params, sample)
+            .pcm_to_mel(sample, threads)
+            .map_err(|e| AudioInkError::Whisper(e.to_string()))?;
+        let (_, probabilities) = state
+            .lang_detect(0, threads)
             .map_err(|e| AudioInkError::Whisper(e.to_string()))?;
 
-        let lang_id = state.full_lang_id_from_state();
+        Ok(top_languages(&probabilities, top_n))
+    }
+
+    /// Idioma de la transcripción: el indicado en las opciones o el detectado
+    /// Devuelve el código y, si se detectó, las probabilidades de los candidatos
+    fn resolve_language(
+        &self,
+        samples: &[f32],
+        options: &EngineOptions,
+        cancel: &CancellationToken,
+    ) -> AudioInkResult<(String, Vec<LanguageProbability>)> {
+        if let Some(code) = options.language.code() {
+            return Ok((code.to_string(), Vec::new()));
+        }
 
-        Ok(whisper_rs::get_lang_str(lang_id).unwrap_or("unknown").to_string())
+        let probabilities = self.detect_language(samples, LANGUAGE_CANDIDATES, cancel)?;
+        let code = probabilities
+            .first()
+            .map(|p| p.code.clone())
+            .unwrap_or_else(|| "unknown".to_string());
+        Ok((code, probabilities))
+    }
+
+    /// Crea un estado y ejecuta la inferencia completa
+    /// El callback de abort de Whisper detiene la inferencia cuando se cancela el token
+    fn run_full(
+        &self,
+        mut params: FullParams,
+        samples: &[f32],
+        cancel: &CancellationToken,
+    ) -> AudioInkResult<WhisperState> {
+        cancel.check()?;
+
+        let abort_token = cancel.clone();
+        params.set_abort_callback_safe(move || abort_token.is_cancelled());
+
+        let mut state = self
+            .context
+            .create_state()
+            .map_err(|e| AudioInkError::Whisper(e.to_string()))?;
+
+        let result = state.full(params, samples);
+
+        // Una inferencia abortada puede devolver error o un resultado parcial
+        cancel.check()?;
+        result.map_err(|e| AudioInkError::Whisper(e.to_string()))?;
+
+        Ok(state)
     }
 
     /// Retorna el nombre del modelo cargado
@@ -244,6 +608,250 @@ impl WhisperEngine {
     }
 }
 
+/// Carga un modelo ggml en un contexto de Whisper
+fn load_context(path: &Path) -> AudioInkResult<WhisperContext> {
+    let path = path
+        .to_str()
+        .ok_or_else(|| AudioInkError::FileError(format!("Ruta no válida: {}", path.display())))?;
+    WhisperContext::new_with_params(path, WhisperContextParameters::default())
+        .map_err(|e| AudioInkError::Whisper(format!("Error al cargar modelo: {}", e)))
+}
+
+/// Token de Whisper con su tiempo y probabilidad
+struct TokenTiming {
+    bytes: Vec<u8>,
+    start_ms: i64,
+    end_ms: i64,
+    probability: f32,
+}
+
+/// Parámetros de Whisper con el perfil de decodificación aplicado
+fn decoding_params<'a, 'b>(decoding: &DecodingOptions) -> FullParams<'a, 'b> {
+    let strategy = match decoding.strategy {
+        DecodingStrategy::Greedy { best_of } => SamplingStrategy::Greedy {
+            best_of: best_of.max(1) as i32,
+        },
+        DecodingStrategy::Beam { beam_size } => SamplingStrategy::BeamSearch {
+            beam_size: beam_size.max(1) as i32,
+            patience: -1.0, // Sin paciencia: comportamiento por defecto de whisper.cpp
+        },
+    };
+
+    let mut params = FullParams::new(strategy);
+    params.set_temperature(decoding.temperature.clamp(0.0, 1.0));
+    params.set_temperature_inc(decoding.temperature_inc.max(0.0));
+    params.set_entropy_thold(decoding.entropy_threshold);
+    params.set_logprob_thold(decoding.logprob_threshold);
+    params.set_no_speech_thold(decoding.no_speech_threshold);
+    params
+}
+
+/// Hilos de Whisper para cada worker en paralelo
+///
+/// Los fijados por el usuario o, en automático, los núcleos repartidos entre
+/// los workers. 0 deja el criterio por defecto de whisper.cpp.
+fn threads_per_worker(n_threads: usize, workers: usize) -> usize {
+    if n_threads > 0 || workers <= 1 {
+        return n_threads;
+    }
+    let cores = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    (cores / workers).max(1)
+}
+
+/// Hilos para la detección de idioma (mismo criterio que `FullParams` por defecto)
+fn detection_threads() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(4)
+}
+
+/// Los `top_n` idiomas más probables, a partir de las probabilidades indexadas por ID de idioma
+fn top_languages(probabilities: &[f32], top_n: usize) -> Vec<LanguageProbability> {
+    let mut ranked: Vec<(usize, f32)> = probabilities.iter().copied().enumerate().collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    ranked
+        .into_iter()
+        .filter_map(|(id, probability)| {
+            whisper_rs::get_lang_str(id as i32).map(|code| LanguageProbability {
+                code: code.to_string(),
+                probability,
+            })
+        })
+        .take(top_n)
+        .collect()
+}
+
+/// Longitud máxima del texto arrastrado del chunk anterior
+/// Whisper usa como contexto como mucho la mitad de su ventana de 448 tokens
+const MAX_CARRIED_PROMPT_CHARS: usize = 600;
+
+/// Construye el prompt de un chunk: el prompt del usuario seguido del final del texto anterior
+/// Los bytes NUL se quitan: whisper-rs pasa el prompt como `CString` y entra en pánico con ellos
+fn build_chunk_prompt(user_prompt: Option<&str>, previous_text: &str) -> Option<String> {
+    let user_prompt = user_prompt.map(|p| p.replace('\0', ""));
+    let user_prompt = user_prompt.as_deref().map(str::trim).filter(|p| !p.is_empty());
+    let previous_text = previous_text.trim();
+
+    // Recortar por el principio en un límite de palabra
+    let carried = if previous_text.chars().count() > MAX_CARRIED_PROMPT_CHARS {
+        let skip = previous_text.chars().count() - MAX_CARRIED_PROMPT_CHARS;
+        let cut = previous_text.char_indices().nth(skip).map(|(i, _)| i).unwrap_or(0);
+        let tail = &previous_text[cut..];
+        tail.split_once(char::is_whitespace).map(|(_, rest)| rest).unwrap_or(tail)
+    } else {
+        previous_text
+    };
+
+    match (user_prompt, carried.is_empty()) {
+        (None, true) => None,
+        (Some(prompt), true) => Some(prompt.to_string()),
+        (None, false) => Some(carried.to_string()),
+        (Some(prompt), false) => Some(format!("{} {}", prompt, carried)),
+    }
+}
+
+/// Quita de un chunk el texto que ya se transcribió en el chunk anterior
+///
+/// Los segmentos que terminan dentro del solape se descartan; del primero que
+/// lo atraviesa se eliminan las palabras iniciales que repiten el final del
+/// texto anterior.
+fn dedupe_overlap(previous: &[Segment], segments: &mut Vec<Segment>, new_audio_start_ms: i64) {
+    segments.retain(|s| s.end_ms > new_audio_start_ms);
+
+    let Some(first) = segments.first_mut() else {
+        return;
+    };
+    if first.start_ms >= new_audio_start_ms {
+        return;
+    }
+
+    let tail: Vec<String> = previous
+        .iter()
+        .rev()
+        .take(3)
+        .rev()
+        .flat_map(|s| s.text.split_whitespace())
+        .map(normalize_word)
+        .collect();
+    let words: Vec<&str> = first.text.split_whitespace().collect();
+    let normalized: Vec<String> = words.iter().map(|w| normalize_word(w)).collect();
+
+    // Mayor k tal que las k primeras palabras repiten las k últimas del texto anterior
+    let repeated = (1..=normalized.len().min(tail.len()))
+        .rev()
+        .find(|&k| tail[tail.len() - k..] == normalized[..k])
+        .unwrap_or(0);
+
+    if repeated > 0 {
+        first.text = words[repeated..].join(" ");
+        first.words.retain(|w| w.end_ms > new_audio_start_ms);
+        if first.text.is_empty() {
+            segments.remove(0);
+        }
+    }
+}
+
+/// Normaliza una palabra para compararla: minúsculas y sin puntuación
+pub(crate) fn normalize_word(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Tokens de control de Whisper (`[_BEG_]`, `[_TT_150]`, `<|endoftext|>`, ...)
+fn is_special_token(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"[_") || bytes.starts_with(b"<|")
+}
+
+/// Marca los segmentos con el idioma en que se transcribieron
+fn tag_language(segments: &mut [Segment], language: &Language) {
+    let code = language.code().map(str::to_string);
+    for segment in segments.iter_mut() {
+        segment.language = code.clone();
+    }
+}
+
+/// Devuelve los tiempos de los segmentos a la línea de tiempo original tras la VAD
+fn remap_segments(segments: &mut [Segment], timeline: Option<&TimelineMap>) {
+    if let Some(timeline) = timeline {
+        for segment in segments.iter_mut() {
+            segment.map_times(|ms| timeline.to_original_ms(ms));
+        }
+    }
+}
+
+/// Agrupa tokens BPE en palabras
+///
+/// Un token que empieza con espacio abre una palabra nueva; los demás se
+/// concatenan a la palabra en curso (los tokens pueden partir caracteres
+/// UTF-8, por eso se acumulan bytes). Los tokens especiales (`[_BEG_]`,
+/// `<|endoftext|>`, ...) se descartan. La probabilidad de cada palabra es la
+/// media de las probabilidades de sus tokens.
+fn group_tokens_into_words(tokens: &[TokenTiming]) -> Vec<Word> {
+    struct Pending {
+        bytes: Vec<u8>,
+        start_ms: i64,
+        end_ms: i64,
+        probabilities: Vec<f32>,
+    }
+
+    fn finish(pending: Pending, words: &mut Vec<Word>) {
+        let text = String::from_utf8_lossy(&pending.bytes).trim().to_string();
+        if text.is_empty() {
+            return;
+        }
+        let probability =
+            pending.probabilities.iter().sum::<f32>() / pending.probabilities.len() as f32;
+        words.push(Word {
+            start_ms: pending.start_ms,
+            end_ms: pending.end_ms,
+            text,
+            probability,
+        });
+    }
+
+    let mut words = Vec::new();
+    let mut current: Option<Pending> = None;
+
+    for token in tokens {
+        if is_special_token(&token.bytes) {
+            continue;
+        }
+
+        let starts_word = token.bytes.first().is_some_and(|b| b.is_ascii_whitespace());
+
+        match current.as_mut() {
+            Some(pending) if !starts_word => {
+                pending.bytes.extend_from_slice(&token.bytes);
+                pending.end_ms = token.end_ms;
+                pending.probabilities.push(token.probability);
+            }
+            _ => {
+                if let Some(pending) = current.take() {
+                    finish(pending, &mut words);
+                }
+                current = Some(Pending {
+                    bytes: token.bytes.clone(),
+                    start_ms: token.start_ms,
+                    end_ms: token.end_ms,
+                    probabilities: vec![token.probability],
+                });
+            }
+        }
+    }
+
+    if let Some(pending) = current {
+        finish(pending, &mut words);
+    }
+
+    words
+}
+
 /// Wrapper thread-safe para el motor Whisper
 pub struct WhisperEngineWrapper(pub Arc<WhisperEngine>);
 
@@ -262,18 +870,10 @@ impl WhisperEngineWrapper {
 unsafe impl Send for WhisperEngine {}
 unsafe impl Sync for WhisperEngine {}
 
-/// Formatea milisegundos a formato HH:MM:SS
-fn format_timestamp_ms(ms: i64) -> String {
-    let total_seconds = ms / 1000;
-    let hours = total_seconds / 3600;
-    let minutes = (total_seconds % 3600) / 60;
-    let seconds = total_seconds % 60;
-    format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::LANGUAGES;
 
     // Nota: Los tests reales requieren un modelo descargado
     // Estos son tests de estructura
@@ -281,7 +881,115 @@ mod tests {
     #[test]
     fn test_language_code() {
         assert_eq!(Language::Auto.code(), None);
-        assert_eq!(Language::English.code(), Some("en"));
-        assert_eq!(Language::Spanish.code(), Some("es"));
+        assert_eq!(Language::parse("English").and_then(|l| l.code()), Some("en"));
+        assert_eq!(Language::parse("es").and_then(|l| l.code()), Some("es"));
+        assert_eq!(Language::parse("Español").and_then(|l| l.code()), Some("es"));
+        assert_eq!(Language::parse("klingon"), None);
+    }
+
+    #[test]
+    fn test_language_table_matches_whisper() {
+        // Todos los idiomas de whisper.cpp están en la tabla, con el mismo orden de IDs
+        let max_id = whisper_rs::get_lang_max_id();
+        assert_eq!(LANGUAGES.len(), max_id as usize + 1);
+        for id in 0..=max_id {
+            let code = whisper_rs::get_lang_str(id).unwrap();
+            assert_eq!(LANGUAGES[id as usize].code, code);
+        }
+    }
+
+    fn token(text: &str, start_ms: i64, end_ms: i64, probability: f32) -> TokenTiming {
+        TokenTiming {
+            bytes: text.as_bytes().to_vec(),
+            start_ms,
+            end_ms,
+            probability,
+        }
+    }
+
+    #[test]
+    fn test_top_languages() {
+        let mut probabilities = vec![0.0; LANGUAGES.len()];
+        probabilities[0] = 0.2; // en
+        probabilities[3] = 0.7; // es
+        probabilities[8] = 0.1; // pt
+
+        let top = top_languages(&probabilities, 2);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].code, "es");
+        assert_eq!(top[1].code, "en");
+        assert!((top[0].probability - 0.7).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_build_chunk_prompt() {
+        assert_eq!(build_chunk_prompt(None, ""), None);
+        assert_eq!(build_chunk_prompt(Some("  "), " "), None);
+        assert_eq!(build_chunk_prompt(Some("AudioInk, Tauri."), ""), Some("AudioInk, Tauri.".to_string()));
+        assert_eq!(
+            build_chunk_prompt(Some("AudioInk."), "we shipped it"),
+            Some("AudioInk. we shipped it".to_string())
+        );
+
+        // El texto arrastrado se recorta por el principio sin partir palabras
+        let long = "palabra ".repeat(200);
+        let prompt = build_chunk_prompt(None, &long).unwrap();
+        assert!(prompt.chars().count() <= MAX_CARRIED_PROMPT_CHARS);
+        assert!(prompt.starts_with("palabra"));
+
+        // Un NUL haría entrar en pánico a whisper-rs al crear el CString
+        assert_eq!(build_chunk_prompt(Some("Audio\0Ink"), ""), Some("AudioInk".to_string()));
+        assert_eq!(build_chunk_prompt(Some("\0"), ""), None);
+    }
+
+    #[test]
+    fn test_dedupe_overlap() {
+        let previous = vec![Segment::new(55_000, 59_500, "and that is why we moved.")];
+        let mut next = vec![
+            Segment::new(58_000, 59_000, "we moved"),
+            Segment::new(59_000, 62_000, "That is why we moved. Then we"),
+            Segment::new(62_000, 64_000, "started again."),
+        ];
+
+        dedupe_overlap(&previous, &mut next, 60_000);
+
+        assert_eq!(next.len(), 2);
+        assert_eq!(next[0].text, "Then we");
+        assert_eq!(next[1].text, "started again.");
+    }
+
+    #[test]
+    fn test_group_tokens_into_words() {
+        let tokens = vec![
+            token("[_BEG_]", 0, 0, 1.0),
+            token(" Hel", 0, 200, 0.9),
+            token("lo", 200, 400, 0.5),
+            token(",", 400, 420, 1.0),
+            token(" world", 500, 900, 0.8),
+            token("<|endoftext|>", 900, 900, 1.0),
+        ];
+
+        let words = group_tokens_into_words(&tokens);
+        assert_eq!(words.len(), 2);
+
+        assert_eq!(words[0].text, "Hello,");
+        assert_eq!((words[0].start_ms, words[0].end_ms), (0, 420));
+        assert!((words[0].probability - 0.8).abs() < 1e-6);
+
+        assert_eq!(words[1].text, "world");
+        assert_eq!((words[1].start_ms, words[1].end_ms), (500, 900));
+    }
+
+    #[test]
+    fn test_group_tokens_joins_split_utf8() {
+        // "ñ" = 0xC3 0xB1, partido entre dos tokens
+        let tokens = vec![
+            TokenTiming { bytes: vec![b' ', b'a', 0xC3], start_ms: 0, end_ms: 100, probability: 0.6 },
+            TokenTiming { bytes: vec![0xB1, b'o'], start_ms: 100, end_ms: 200, probability: 0.4 },
+        ];
+
+        let words = group_tokens_into_words(&tokens);
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].text, "año");
     }
 }
@@ -1,5 +1,6 @@
+use crate::core::{run_command_cancellable, CancellationToken};
 use crate::utils::{get_ytdlp_install_instructions, AudioInkError, AudioInkResult};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Result of downloading YouTube audio
@@ -18,17 +19,13 @@ const YTDLP_PATHS: &[&str] = &[
 
 /// Find the yt-dlp binary path
 fn find_ytdlp() -> Option<&'static str> {
-    for path in YTDLP_PATHS {
-        if Command::new(path)
+    YTDLP_PATHS.iter().copied().find(|path| {
+        Command::new(path)
             .arg("--version")
             .output()
             .map(|o| o.status.success())
             .unwrap_or(false)
-        {
-            return Some(path);
-        }
-    }
-    None
+    })
 }
 
 /// Check if yt-dlp is available in the system
@@ -37,22 +34,32 @@ pub fn is_ytdlp_available() -> bool {
 }
 
 /// Download audio from YouTube video using yt-dlp
-pub fn download_youtube_audio(url: &str) -> AudioInkResult<YouTubeDownloadResult> {
+///
+/// Each download gets its own directory under the shared temp dir, so a failed
+/// or cancelled job only removes its own files. If `cancel` is triggered, yt-dlp
+/// is killed and its partial files are removed.
+pub fn download_youtube_audio(
+    url: &str,
+    cancel: &CancellationToken,
+) -> AudioInkResult<YouTubeDownloadResult> {
     let ytdlp = find_ytdlp().ok_or_else(|| {
         AudioInkError::Internal(get_ytdlp_install_instructions().to_string())
     })?;
 
-    // Create temp directory for download
-    let temp_dir = std::env::temp_dir().join("audioink_youtube");
+    // Create a temp directory for this download only
+    let temp_dir = std::env::temp_dir()
+        .join("audioink_youtube")
+        .join(uuid::Uuid::new_v4().to_string());
     std::fs::create_dir_all(&temp_dir).map_err(|e| {
         AudioInkError::Internal(format!("Failed to create temp directory: {}", e))
     })?;
 
     // First, get the video title
-    let title_output = Command::new(ytdlp)
-        .args(["--get-title", url])
-        .output()
-        .map_err(|e| AudioInkError::Internal(format!("Failed to get video title: {}", e)))?;
+    let title_output = run_command_cancellable(
+        Command::new(ytdlp).args(["--get-title", url]),
+        "yt-dlp",
+        cancel,
+    )?;
 
     let title = if title_output.status.success() {
         String::from_utf8_lossy(&title_output.stdout)
@@ -73,20 +80,23 @@ pub fn download_youtube_audio(url: &str) -> AudioInkResult<YouTubeDownloadResult
     let output_template = temp_dir.join(format!("{}.%(ext)s", safe_title));
 
     // Download audio only in best quality, convert to wav for whisper
-    let output = Command::new(ytdlp)
-        .args([
-            "-x",                           // Extract audio
-            "--audio-format", "wav",        // Convert to WAV (best for whisper)
-            "--audio-quality", "0",         // Best quality
-            "-o", output_template.to_str().unwrap(),
-            "--no-playlist",                // Don't download playlist
-            "--no-warnings",
-            url,
-        ])
-        .output()
-        .map_err(|e| AudioInkError::Internal(format!("Failed to run yt-dlp: {}", e)))?;
+    let mut command = Command::new(ytdlp);
+    command.args([
+        "-x",                           // Extract audio
+        "--audio-format", "wav",        // Convert to WAV (best for whisper)
+        "--audio-quality", "0",         // Best quality
+        "-o", output_template.to_str().unwrap(),
+        "--no-playlist",                // Don't download playlist
+        "--no-warnings",
+        url,
+    ]);
+
+    let output = run_command_cancellable(&mut command, "yt-dlp", cancel).inspect_err(|_| {
+        cleanup_download_dir(&temp_dir);
+    })?;
 
     if !output.status.success() {
+        cleanup_download_dir(&temp_dir);
         let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(AudioInkError::Internal(format!(
             "yt-dlp failed: {}",
@@ -98,7 +108,7 @@ pub fn download_youtube_audio(url: &str) -> AudioInkResult<YouTubeDownloadResult
     let audio_path = temp_dir.join(format!("{}.wav", safe_title));
 
     if !audio_path.exists() {
-        // Try to find any audio file in the temp directory
+        // Try to find any audio file in this download's directory
         let entries = std::fs::read_dir(&temp_dir)
             .map_err(|e| AudioInkError::Internal(format!("Failed to read temp dir: {}", e)))?;
 
@@ -114,6 +124,7 @@ pub fn download_youtube_audio(url: &str) -> AudioInkResult<YouTubeDownloadResult
             }
         }
 
+        cleanup_download_dir(&temp_dir);
         return Err(AudioInkError::Internal(
             "Downloaded audio file not found".to_string()
         ));
@@ -125,7 +136,48 @@ pub fn download_youtube_audio(url: &str) -> AudioInkResult<YouTubeDownloadResult
     })
 }
 
-/// Clean up downloaded files
+/// Clean up downloaded files, including the download's own temp directory
 pub fn cleanup_youtube_audio(path: &PathBuf) {
-    let _ = std::fs::remove_file(path);
+    match path.parent() {
+        Some(dir) if is_download_dir(dir) => cleanup_download_dir(dir),
+        _ => {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Remove a download's directory with everything yt-dlp left in it (`.part`,
+/// `.ytdl`, intermediate formats) after a failed or cancelled run
+fn cleanup_download_dir(temp_dir: &Path) {
+    let _ = std::fs::remove_dir_all(temp_dir);
+}
+
+/// Whether `dir` is a per-download directory inside `audioink_youtube`
+fn is_download_dir(dir: &Path) -> bool {
+    dir.parent()
+        .and_then(|parent| parent.file_name())
+        .is_some_and(|name| name == "audioink_youtube")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cleanup_only_removes_own_download() {
+        let root = tempfile::tempdir().unwrap();
+        let shared = root.path().join("audioink_youtube");
+        let job = shared.join("job-1");
+        let other = shared.join("job-2");
+        std::fs::create_dir_all(&job).unwrap();
+        std::fs::create_dir_all(&other).unwrap();
+        std::fs::write(job.join("Interview.wav"), b"a").unwrap();
+        std::fs::write(job.join("Interview.wav.part"), b"a").unwrap();
+        std::fs::write(other.join("Interview 2.wav"), b"b").unwrap();
+
+        cleanup_youtube_audio(&job.join("Interview.wav"));
+
+        assert!(!job.exists());
+        assert!(other.join("Interview 2.wav").exists());
+    }
 }
@@ -2,12 +2,16 @@ pub mod commands;
 pub mod core;
 pub mod models;
 pub mod persistence;
+pub mod server;
+pub mod service;
 pub mod utils;
 
 use commands::{
     // Transcription commands
     transcribe_file,
     transcribe_youtube,
+    cancel_transcription,
+    detect_language,
     check_ytdlp_available,
     get_languages,
     get_supported_formats,
@@ -18,16 +22,38 @@ use commands::{
     delete_transcription,
     clear_history,
     get_history_count,
+    export_transcription_subtitles,
     // Model commands
     list_models,
     get_downloaded_models,
     check_model_downloaded,
     download_whisper_model,
+    cancel_model_download,
+    get_model_downloads,
     delete_whisper_model,
     get_model_path_cmd,
+    get_loaded_models,
+    unload_model,
+    import_model,
+    verify_models,
     // YouTube commands
     check_youtube_captions,
     get_youtube_captions,
+    // Queue commands
+    start_queue_worker,
+    enqueue_transcriptions,
+    list_jobs,
+    move_job,
+    remove_job,
+    // API server commands
+    start_api_server,
+    stop_api_server,
+    get_api_server_status,
+    // Settings commands
+    get_settings,
+    update_settings,
+    // Storage commands
+    get_storage_info,
 };
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -37,10 +63,16 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .manage(AppState::new())
+        .setup(|app| {
+            start_queue_worker(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // Transcription
             transcribe_file,
             transcribe_youtube,
+            cancel_transcription,
+            detect_language,
             check_ytdlp_available,
             get_languages,
             get_supported_formats,
@@ -50,16 +82,37 @@ pub fn run() {
             delete_transcription,
             clear_history,
             get_history_count,
+            export_transcription_subtitles,
             // Models
             list_models,
             get_downloaded_models,
             check_model_downloaded,
             download_whisper_model,
+            cancel_model_download,
+            get_model_downloads,
             delete_whisper_model,
             get_model_path_cmd,
+            get_loaded_models,
+            unload_model,
+            import_model,
+            verify_models,
             // YouTube
             check_youtube_captions,
             get_youtube_captions,
+            // Queue
+            enqueue_transcriptions,
+            list_jobs,
+            move_job,
+            remove_job,
+            // API server
+            start_api_server,
+            stop_api_server,
+            get_api_server_status,
+            // Settings
+            get_settings,
+            update_settings,
+            // Storage
+            get_storage_info,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
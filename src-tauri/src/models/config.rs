@@ -1,127 +1,362 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-/// Modelos de Whisper disponibles
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
+/// Modelo del catálogo de whisper.cpp
+#[derive(Debug, PartialEq, Eq)]
+pub struct ModelSpec {
+    /// Nombre del modelo ("base", "base.en", "base-q5_1", ...)
+    pub name: &'static str,
+    /// Nombre del archivo en el repositorio de whisper.cpp
+    pub filename: &'static str,
+    /// Tamaño aproximado del archivo en bytes
+    pub size_bytes: u64,
+    pub description: &'static str,
+    /// Solo transcribe inglés (variantes `.en`)
+    pub english_only: bool,
+    /// Cuantización de los pesos (`q5_0`, `q5_1`, `q8_0`); None = precisión completa
+    pub quantization: Option<&'static str>,
+    /// SHA-256 del archivo publicado (el `oid` LFS de Hugging Face)
+    /// Tiene prioridad sobre cualquier hash leído del origen de la descarga
+    pub sha256: Option<&'static str>,
+}
+
+const fn spec(
+    name: &'static str,
+    filename: &'static str,
+    size_bytes: u64,
+    description: &'static str,
+    english_only: bool,
+    quantization: Option<&'static str>,
+    sha256: Option<&'static str>,
+) -> ModelSpec {
+    ModelSpec {
+        name,
+        filename,
+        size_bytes,
+        description,
+        english_only,
+        quantization,
+        sha256,
+    }
+}
+
+/// Origen por defecto de los modelos del catálogo
+pub const DEFAULT_MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
+/// Modelos descargables de https://huggingface.co/ggerganov/whisper.cpp
+///
+/// Las variantes `.en` solo entienden inglés y son algo más precisas en él.
+/// Las cuantizadas ocupan menos memoria y son más rápidas en CPU, con una
+/// pérdida de calidad pequeña (q8_0) o moderada (q5).
+pub const MODEL_CATALOG: &[ModelSpec] = &[
+    spec("tiny", "ggml-tiny.bin", 75_000_000, "Ultra rápido, menor calidad", false, None, None),
+    spec("tiny.en", "ggml-tiny.en.bin", 75_000_000, "Ultra rápido, solo inglés", true, None, None),
+    spec("tiny-q5_1", "ggml-tiny-q5_1.bin", 32_000_000, "Tiny cuantizado (5 bits)", false, Some("q5_1"), None),
+    spec("tiny.en-q5_1", "ggml-tiny.en-q5_1.bin", 32_000_000, "Tiny cuantizado (5 bits), solo inglés", true, Some("q5_1"), None),
+    spec("tiny-q8_0", "ggml-tiny-q8_0.bin", 44_000_000, "Tiny cuantizado (8 bits)", false, Some("q8_0"), None),
+    spec("tiny.en-q8_0", "ggml-tiny.en-q8_0.bin", 44_000_000, "Tiny cuantizado (8 bits), solo inglés", true, Some("q8_0"), None),
+    spec("base", "ggml-base.bin", 142_000_000, "Balance entre velocidad y calidad", false, None, None),
+    spec("base.en", "ggml-base.en.bin", 142_000_000, "Balance entre velocidad y calidad, solo inglés", true, None, None),
+    spec("base-q5_1", "ggml-base-q5_1.bin", 60_000_000, "Base cuantizado (5 bits)", false, Some("q5_1"), None),
+    spec("base.en-q5_1", "ggml-base.en-q5_1.bin", 60_000_000, "Base cuantizado (5 bits), solo inglés", true, Some("q5_1"), None),
+    spec("base-q8_0", "ggml-base-q8_0.bin", 82_000_000, "Base cuantizado (8 bits)", false, Some("q8_0"), None),
+    spec("base.en-q8_0", "ggml-base.en-q8_0.bin", 82_000_000, "Base cuantizado (8 bits), solo inglés", true, Some("q8_0"), None),
+    spec("small", "ggml-small.bin", 466_000_000, "Buena calidad, velocidad moderada", false, None, None),
+    spec("small.en", "ggml-small.en.bin", 466_000_000, "Buena calidad, velocidad moderada, solo inglés", true, None, None),
+    spec("small-q5_1", "ggml-small-q5_1.bin", 190_000_000, "Small cuantizado (5 bits)", false, Some("q5_1"), None),
+    spec("small.en-q5_1", "ggml-small.en-q5_1.bin", 190_000_000, "Small cuantizado (5 bits), solo inglés", true, Some("q5_1"), None),
+    spec("small-q8_0", "ggml-small-q8_0.bin", 264_000_000, "Small cuantizado (8 bits)", false, Some("q8_0"), None),
+    spec("small.en-q8_0", "ggml-small.en-q8_0.bin", 264_000_000, "Small cuantizado (8 bits), solo inglés", true, Some("q8_0"), None),
+    spec("medium", "ggml-medium.bin", 1_500_000_000, "Alta calidad, más lento", false, None, None),
+    spec("medium.en", "ggml-medium.en.bin", 1_500_000_000, "Alta calidad, más lento, solo inglés", true, None, None),
+    spec("medium-q5_0", "ggml-medium-q5_0.bin", 539_000_000, "Medium cuantizado (5 bits)", false, Some("q5_0"), None),
+    spec("medium.en-q5_0", "ggml-medium.en-q5_0.bin", 539_000_000, "Medium cuantizado (5 bits), solo inglés", true, Some("q5_0"), None),
+    spec("medium-q8_0", "ggml-medium-q8_0.bin", 823_000_000, "Medium cuantizado (8 bits)", false, Some("q8_0"), None),
+    spec("medium.en-q8_0", "ggml-medium.en-q8_0.bin", 823_000_000, "Medium cuantizado (8 bits), solo inglés", true, Some("q8_0"), None),
+    spec("large", "ggml-large.bin", 2_900_000_000, "Mejor calidad, el más lento", false, None, None),
+    spec("large-v3-q5_0", "ggml-large-v3-q5_0.bin", 1_080_000_000, "Large v3 cuantizado (5 bits)", false, Some("q5_0"), None),
+    spec("large-v3-turbo", "ggml-large-v3-turbo.bin", 1_620_000_000, "Excelente calidad, más rápido que large", false, None, None),
+    spec("large-v3-turbo-q5_0", "ggml-large-v3-turbo-q5_0.bin", 574_000_000, "Turbo cuantizado (5 bits)", false, Some("q5_0"), None),
+    spec("large-v3-turbo-q8_0", "ggml-large-v3-turbo-q8_0.bin", 874_000_000, "Turbo cuantizado (8 bits)", false, Some("q8_0"), None),
+];
+
+/// Modelo importado por el usuario (p. ej. un fine-tune propio)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CustomModel {
+    /// Identificador con el que se selecciona el modelo
+    pub name: String,
+    /// Nombre elegido al importarlo
+    pub display_name: String,
+    /// Archivo dentro del directorio de modelos
+    pub filename: String,
+    pub size_bytes: u64,
+    pub imported_at: DateTime<Utc>,
+}
+
+/// Modelo de Whisper: del catálogo o importado
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum WhisperModel {
-    Tiny,
-    Base,
-    Small,
-    Medium,
-    Large,
-    #[serde(rename = "large-v3-turbo")]
-    LargeV3Turbo,
+    Catalog(&'static ModelSpec),
+    Custom(CustomModel),
 }
 
 impl WhisperModel {
+    pub const TINY: WhisperModel = WhisperModel::Catalog(&MODEL_CATALOG[0]);
+    pub const BASE: WhisperModel = WhisperModel::Catalog(&MODEL_CATALOG[6]);
+    pub const SMALL: WhisperModel = WhisperModel::Catalog(&MODEL_CATALOG[12]);
+    pub const MEDIUM: WhisperModel = WhisperModel::Catalog(&MODEL_CATALOG[18]);
+    pub const LARGE: WhisperModel = WhisperModel::Catalog(&MODEL_CATALOG[24]);
+    pub const LARGE_V3_TURBO: WhisperModel = WhisperModel::Catalog(&MODEL_CATALOG[26]);
+
+    /// Busca un modelo del catálogo por nombre ("base", "small.en", "medium-q5_0", ...)
+    /// Los importados se resuelven con `core::resolve_model`
+    pub fn from_name(name: &str) -> Option<WhisperModel> {
+        let name = name.trim().to_lowercase();
+        MODEL_CATALOG
+            .iter()
+            .find(|spec| spec.name == name)
+            .map(WhisperModel::Catalog)
+    }
+
+    /// Datos del catálogo (None para modelos importados)
+    pub fn spec(&self) -> Option<&'static ModelSpec> {
+        match self {
+            WhisperModel::Catalog(spec) => Some(spec),
+            WhisperModel::Custom(_) => None,
+        }
+    }
+
+    /// Identificador del modelo
+    pub fn name(&self) -> &str {
+        match self {
+            WhisperModel::Catalog(spec) => spec.name,
+            WhisperModel::Custom(custom) => &custom.name,
+        }
+    }
+
     /// Nombre del archivo del modelo
-    pub fn filename(&self) -> &'static str {
+    pub fn filename(&self) -> &str {
         match self {
-            WhisperModel::Tiny => "ggml-tiny.bin",
-            WhisperModel::Base => "ggml-base.bin",
-            WhisperModel::Small => "ggml-small.bin",
-            WhisperModel::Medium => "ggml-medium.bin",
-            WhisperModel::Large => "ggml-large.bin",
-            WhisperModel::LargeV3Turbo => "ggml-large-v3-turbo.bin",
+            WhisperModel::Catalog(spec) => spec.filename,
+            WhisperModel::Custom(custom) => &custom.filename,
         }
     }
 
-    /// URL de descarga del modelo (Hugging Face)
-    pub fn download_url(&self) -> String {
-        format!(
-            "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/{}",
-            self.filename()
-        )
+    /// URL de descarga del modelo (Hugging Face). None para modelos importados
+    pub fn download_url(&self) -> Option<String> {
+        self.download_url_from(DEFAULT_MODEL_BASE_URL)
+    }
+
+    /// URL de descarga desde otro origen con la misma estructura (un mirror)
+    pub fn download_url_from(&self, base_url: &str) -> Option<String> {
+        self.spec()
+            .map(|spec| format!("{}/{}", base_url.trim_end_matches('/'), spec.filename))
     }
 
-    /// Tamaño aproximado del modelo en bytes
+    /// SHA-256 fijado en el catálogo (None si no se conoce o es importado)
+    pub fn sha256(&self) -> Option<&'static str> {
+        self.spec().and_then(|spec| spec.sha256)
+    }
+
+    /// Tamaño del modelo en bytes (aproximado para los del catálogo)
     pub fn size_bytes(&self) -> u64 {
         match self {
-            WhisperModel::Tiny => 75_000_000,      // ~75 MB
-            WhisperModel::Base => 142_000_000,     // ~142 MB
-            WhisperModel::Small => 466_000_000,    // ~466 MB
-            WhisperModel::Medium => 1_500_000_000, // ~1.5 GB
-            WhisperModel::Large => 2_900_000_000,  // ~2.9 GB
-            WhisperModel::LargeV3Turbo => 809_000_000, // ~809 MB
+            WhisperModel::Catalog(spec) => spec.size_bytes,
+            WhisperModel::Custom(custom) => custom.size_bytes,
         }
     }
 
-    /// Descripción del modelo
-    pub fn description(&self) -> &'static str {
+    /// Descripción del modelo (el nombre elegido para los importados)
+    pub fn description(&self) -> &str {
         match self {
-            WhisperModel::Tiny => "Ultra rápido, menor calidad",
-            WhisperModel::Base => "Balance entre velocidad y calidad",
-            WhisperModel::Small => "Buena calidad, velocidad moderada",
-            WhisperModel::Medium => "Alta calidad, más lento",
-            WhisperModel::Large => "Mejor calidad, el más lento",
-            WhisperModel::LargeV3Turbo => "Excelente calidad, más rápido que large",
+            WhisperModel::Catalog(spec) => spec.description,
+            WhisperModel::Custom(custom) => &custom.display_name,
         }
     }
 
-    /// Lista todos los modelos disponibles
+    /// Solo transcribe inglés
+    pub fn is_english_only(&self) -> bool {
+        self.spec().is_some_and(|spec| spec.english_only)
+    }
+
+    /// Modelo importado por el usuario
+    pub fn is_custom(&self) -> bool {
+        matches!(self, WhisperModel::Custom(_))
+    }
+
+    /// Lista los modelos del catálogo
     pub fn all() -> Vec<WhisperModel> {
-        vec![
-            WhisperModel::Tiny,
-            WhisperModel::Base,
-            WhisperModel::Small,
-            WhisperModel::Medium,
-            WhisperModel::Large,
-            WhisperModel::LargeV3Turbo,
-        ]
+        MODEL_CATALOG.iter().map(WhisperModel::Catalog).collect()
     }
 }
 
 impl Default for WhisperModel {
     fn default() -> Self {
-        WhisperModel::Base
+        WhisperModel::BASE
     }
 }
 
 impl std::fmt::Display for WhisperModel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            WhisperModel::Tiny => write!(f, "tiny"),
-            WhisperModel::Base => write!(f, "base"),
-            WhisperModel::Small => write!(f, "small"),
-            WhisperModel::Medium => write!(f, "medium"),
-            WhisperModel::Large => write!(f, "large"),
-            WhisperModel::LargeV3Turbo => write!(f, "large-v3-turbo"),
-        }
+        write!(f, "{}", self.name())
     }
 }
 
-/// Idiomas soportados para transcripción
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
+/// Idioma soportado por Whisper
+#[derive(Debug, PartialEq, Eq)]
+pub struct LanguageInfo {
+    /// Código ISO usado por Whisper
+    pub code: &'static str,
+    /// Nombre en inglés
+    pub name: &'static str,
+    /// Nombre en el propio idioma
+    pub native_name: &'static str,
+}
+
+/// Idiomas de Whisper, en el orden de sus IDs de idioma
+pub const LANGUAGES: &[LanguageInfo] = &[
+    LanguageInfo { code: "en", name: "English", native_name: "English" },
+    LanguageInfo { code: "zh", name: "Chinese", native_name: "中文" },
+    LanguageInfo { code: "de", name: "German", native_name: "Deutsch" },
+    LanguageInfo { code: "es", name: "Spanish", native_name: "Español" },
+    LanguageInfo { code: "ru", name: "Russian", native_name: "Русский" },
+    LanguageInfo { code: "ko", name: "Korean", native_name: "한국어" },
+    LanguageInfo { code: "fr", name: "French", native_name: "Français" },
+    LanguageInfo { code: "ja", name: "Japanese", native_name: "日本語" },
+    LanguageInfo { code: "pt", name: "Portuguese", native_name: "Português" },
+    LanguageInfo { code: "tr", name: "Turkish", native_name: "Türkçe" },
+    LanguageInfo { code: "pl", name: "Polish", native_name: "Polski" },
+    LanguageInfo { code: "ca", name: "Catalan", native_name: "Català" },
+    LanguageInfo { code: "nl", name: "Dutch", native_name: "Nederlands" },
+    LanguageInfo { code: "ar", name: "Arabic", native_name: "العربية" },
+    LanguageInfo { code: "sv", name: "Swedish", native_name: "Svenska" },
+    LanguageInfo { code: "it", name: "Italian", native_name: "Italiano" },
+    LanguageInfo { code: "id", name: "Indonesian", native_name: "Bahasa Indonesia" },
+    LanguageInfo { code: "hi", name: "Hindi", native_name: "हिन्दी" },
+    LanguageInfo { code: "fi", name: "Finnish", native_name: "Suomi" },
+    LanguageInfo { code: "vi", name: "Vietnamese", native_name: "Tiếng Việt" },
+    LanguageInfo { code: "he", name: "Hebrew", native_name: "עברית" },
+    LanguageInfo { code: "uk", name: "Ukrainian", native_name: "Українська" },
+    LanguageInfo { code: "el", name: "Greek", native_name: "Ελληνικά" },
+    LanguageInfo { code: "ms", name: "Malay", native_name: "Bahasa Melayu" },
+    LanguageInfo { code: "cs", name: "Czech", native_name: "Čeština" },
+    LanguageInfo { code: "ro", name: "Romanian", native_name: "Română" },
+    LanguageInfo { code: "da", name: "Danish", native_name: "Dansk" },
+    LanguageInfo { code: "hu", name: "Hungarian", native_name: "Magyar" },
+    LanguageInfo { code: "ta", name: "Tamil", native_name: "தமிழ்" },
+    LanguageInfo { code: "no", name: "Norwegian", native_name: "Norsk" },
+    LanguageInfo { code: "th", name: "Thai", native_name: "ไทย" },
+    LanguageInfo { code: "ur", name: "Urdu", native_name: "اردو" },
+    LanguageInfo { code: "hr", name: "Croatian", native_name: "Hrvatski" },
+    LanguageInfo { code: "bg", name: "Bulgarian", native_name: "Български" },
+    LanguageInfo { code: "lt", name: "Lithuanian", native_name: "Lietuvių" },
+    LanguageInfo { code: "la", name: "Latin", native_name: "Latina" },
+    LanguageInfo { code: "mi", name: "Maori", native_name: "Te Reo Māori" },
+    LanguageInfo { code: "ml", name: "Malayalam", native_name: "മലയാളം" },
+    LanguageInfo { code: "cy", name: "Welsh", native_name: "Cymraeg" },
+    LanguageInfo { code: "sk", name: "Slovak", native_name: "Slovenčina" },
+    LanguageInfo { code: "te", name: "Telugu", native_name: "తెలుగు" },
+    LanguageInfo { code: "fa", name: "Persian", native_name: "فارسی" },
+    LanguageInfo { code: "lv", name: "Latvian", native_name: "Latviešu" },
+    LanguageInfo { code: "bn", name: "Bengali", native_name: "বাংলা" },
+    LanguageInfo { code: "sr", name: "Serbian", native_name: "Српски" },
+    LanguageInfo { code: "az", name: "Azerbaijani", native_name: "Azərbaycanca" },
+    LanguageInfo { code: "sl", name: "Slovenian", native_name: "Slovenščina" },
+    LanguageInfo { code: "kn", name: "Kannada", native_name: "ಕನ್ನಡ" },
+    LanguageInfo { code: "et", name: "Estonian", native_name: "Eesti" },
+    LanguageInfo { code: "mk", name: "Macedonian", native_name: "Македонски" },
+    LanguageInfo { code: "br", name: "Breton", native_name: "Brezhoneg" },
+    LanguageInfo { code: "eu", name: "Basque", native_name: "Euskara" },
+    LanguageInfo { code: "is", name: "Icelandic", native_name: "Íslenska" },
+    LanguageInfo { code: "hy", name: "Armenian", native_name: "Հայերեն" },
+    LanguageInfo { code: "ne", name: "Nepali", native_name: "नेपाली" },
+    LanguageInfo { code: "mn", name: "Mongolian", native_name: "Монгол" },
+    LanguageInfo { code: "bs", name: "Bosnian", native_name: "Bosanski" },
+    LanguageInfo { code: "kk", name: "Kazakh", native_name: "Қазақ тілі" },
+    LanguageInfo { code: "sq", name: "Albanian", native_name: "Shqip" },
+    LanguageInfo { code: "sw", name: "Swahili", native_name: "Kiswahili" },
+    LanguageInfo { code: "gl", name: "Galician", native_name: "Galego" },
+    LanguageInfo { code: "mr", name: "Marathi", native_name: "मराठी" },
+    LanguageInfo { code: "pa", name: "Punjabi", native_name: "ਪੰਜਾਬੀ" },
+    LanguageInfo { code: "si", name: "Sinhala", native_name: "සිංහල" },
+    LanguageInfo { code: "km", name: "Khmer", native_name: "ខ្មែរ" },
+    LanguageInfo { code: "sn", name: "Shona", native_name: "ChiShona" },
+    LanguageInfo { code: "yo", name: "Yoruba", native_name: "Yorùbá" },
+    LanguageInfo { code: "so", name: "Somali", native_name: "Soomaali" },
+    LanguageInfo { code: "af", name: "Afrikaans", native_name: "Afrikaans" },
+    LanguageInfo { code: "oc", name: "Occitan", native_name: "Occitan" },
+    LanguageInfo { code: "ka", name: "Georgian", native_name: "ქართული" },
+    LanguageInfo { code: "be", name: "Belarusian", native_name: "Беларуская" },
+    LanguageInfo { code: "tg", name: "Tajik", native_name: "Тоҷикӣ" },
+    LanguageInfo { code: "sd", name: "Sindhi", native_name: "سنڌي" },
+    LanguageInfo { code: "gu", name: "Gujarati", native_name: "ગુજરાતી" },
+    LanguageInfo { code: "am", name: "Amharic", native_name: "አማርኛ" },
+    LanguageInfo { code: "yi", name: "Yiddish", native_name: "ייִדיש" },
+    LanguageInfo { code: "lo", name: "Lao", native_name: "ລາວ" },
+    LanguageInfo { code: "uz", name: "Uzbek", native_name: "Oʻzbekcha" },
+    LanguageInfo { code: "fo", name: "Faroese", native_name: "Føroyskt" },
+    LanguageInfo { code: "ht", name: "Haitian Creole", native_name: "Kreyòl Ayisyen" },
+    LanguageInfo { code: "ps", name: "Pashto", native_name: "پښتو" },
+    LanguageInfo { code: "tk", name: "Turkmen", native_name: "Türkmençe" },
+    LanguageInfo { code: "nn", name: "Nynorsk", native_name: "Nynorsk" },
+    LanguageInfo { code: "mt", name: "Maltese", native_name: "Malti" },
+    LanguageInfo { code: "sa", name: "Sanskrit", native_name: "संस्कृतम्" },
+    LanguageInfo { code: "lb", name: "Luxembourgish", native_name: "Lëtzebuergesch" },
+    LanguageInfo { code: "my", name: "Myanmar", native_name: "မြန်မာ" },
+    LanguageInfo { code: "bo", name: "Tibetan", native_name: "བོད་ཡིག" },
+    LanguageInfo { code: "tl", name: "Tagalog", native_name: "Tagalog" },
+    LanguageInfo { code: "mg", name: "Malagasy", native_name: "Malagasy" },
+    LanguageInfo { code: "as", name: "Assamese", native_name: "অসমীয়া" },
+    LanguageInfo { code: "tt", name: "Tatar", native_name: "Татарча" },
+    LanguageInfo { code: "haw", name: "Hawaiian", native_name: "ʻŌlelo Hawaiʻi" },
+    LanguageInfo { code: "ln", name: "Lingala", native_name: "Lingála" },
+    LanguageInfo { code: "ha", name: "Hausa", native_name: "Hausa" },
+    LanguageInfo { code: "ba", name: "Bashkir", native_name: "Башҡортса" },
+    LanguageInfo { code: "jw", name: "Javanese", native_name: "Basa Jawa" },
+    LanguageInfo { code: "su", name: "Sundanese", native_name: "Basa Sunda" },
+    LanguageInfo { code: "yue", name: "Cantonese", native_name: "粵語" },
+];
+
+/// Idioma de una transcripción
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Language {
+    /// Detección automática
+    #[default]
     Auto,
-    English,
-    Spanish,
-    French,
-    German,
-    Italian,
-    Portuguese,
-    Japanese,
-    Chinese,
-    Korean,
-    Russian,
+    Specific(&'static LanguageInfo),
 }
 
 impl Language {
+    /// Busca un idioma por su código ISO
+    pub fn from_code(code: &str) -> Option<Language> {
+        LANGUAGES
+            .iter()
+            .find(|info| info.code == code)
+            .map(Language::Specific)
+    }
+
+    /// Interpreta un código, nombre en inglés o nombre nativo ("auto" = detección automática)
+    pub fn parse(value: &str) -> Option<Language> {
+        let value = value.trim().to_lowercase();
+        if value == "auto" {
+            return Some(Language::Auto);
+        }
+
+        LANGUAGES
+            .iter()
+            .find(|info| {
+                info.code == value
+                    || info.name.to_lowercase() == value
+                    || info.native_name.to_lowercase() == value
+            })
+            .map(Language::Specific)
+    }
+
     /// Código ISO del idioma para Whisper
     pub fn code(&self) -> Option<&'static str> {
         match self {
             Language::Auto => None,
-            Language::English => Some("en"),
-            Language::Spanish => Some("es"),
-            Language::French => Some("fr"),
-            Language::German => Some("de"),
-            Language::Italian => Some("it"),
-            Language::Portuguese => Some("pt"),
-            Language::Japanese => Some("ja"),
-            Language::Chinese => Some("zh"),
-            Language::Korean => Some("ko"),
-            Language::Russian => Some("ru"),
+            Language::Specific(info) => Some(info.code),
         }
     }
 
@@ -129,40 +364,264 @@ impl Language {
     pub fn display_name(&self) -> &'static str {
         match self {
             Language::Auto => "Auto-detect",
-            Language::English => "English",
-            Language::Spanish => "Español",
-            Language::French => "Français",
-            Language::German => "Deutsch",
-            Language::Italian => "Italiano",
-            Language::Portuguese => "Português",
-            Language::Japanese => "日本語",
-            Language::Chinese => "中文",
-            Language::Korean => "한국어",
-            Language::Russian => "Русский",
+            Language::Specific(info) => info.native_name,
         }
     }
 
     /// Lista todos los idiomas disponibles
     pub fn all() -> Vec<Language> {
-        vec![
-            Language::Auto,
-            Language::English,
-            Language::Spanish,
-            Language::French,
-            Language::German,
-            Language::Italian,
-            Language::Portuguese,
-            Language::Japanese,
-            Language::Chinese,
-            Language::Korean,
-            Language::Russian,
-        ]
-    }
-}
-
-impl Default for Language {
+        std::iter::once(Language::Auto)
+            .chain(LANGUAGES.iter().map(Language::Specific))
+            .collect()
+    }
+}
+
+impl Serialize for Language {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code().unwrap_or("auto"))
+    }
+}
+
+impl<'de> Deserialize<'de> for Language {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Language::parse(&value)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown language: {}", value)))
+    }
+}
+
+/// Tarea de Whisper: transcribir en el idioma original o traducir al inglés
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Task {
+    #[default]
+    Transcribe,
+    Translate,
+}
+
+impl Task {
+    /// Indica si el texto resultante es una traducción al inglés
+    pub fn is_translate(&self) -> bool {
+        matches!(self, Task::Translate)
+    }
+}
+
+impl std::fmt::Display for Task {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Task::Transcribe => write!(f, "transcribe"),
+            Task::Translate => write!(f, "translate"),
+        }
+    }
+}
+
+/// Estrategia de muestreo del decodificador de Whisper
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum DecodingStrategy {
+    /// Elige el token más probable; `best_of` candidatos al reintentar con temperatura
+    Greedy { best_of: u32 },
+    /// Búsqueda en haz: más lenta, más robusta en audio difícil
+    Beam { beam_size: u32 },
+}
+
+/// Perfil de decodificación de Whisper
+///
+/// Si un segmento supera `entropy_threshold` o queda por debajo de
+/// `logprob_threshold`, Whisper lo vuelve a decodificar subiendo la temperatura
+/// en `temperature_inc` (0 desactiva los reintentos).
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct DecodingOptions {
+    pub strategy: DecodingStrategy,
+    pub temperature: f32,
+    pub temperature_inc: f32,
+    pub entropy_threshold: f32,
+    pub logprob_threshold: f32,
+    pub no_speech_threshold: f32,
+}
+
+/// Nombres de los perfiles predefinidos
+pub const DECODING_PRESETS: &[&str] = &["default", "fast", "accurate"];
+
+impl DecodingOptions {
+    /// Greedy sin reintentos: el más rápido
+    pub fn fast() -> Self {
+        Self {
+            temperature_inc: 0.0,
+            ..Self::default()
+        }
+    }
+
+    /// Beam search de 5 con reintentos por temperatura
+    pub fn accurate() -> Self {
+        Self {
+            strategy: DecodingStrategy::Beam { beam_size: 5 },
+            ..Self::default()
+        }
+    }
+
+    /// Busca un perfil predefinido por nombre
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "default" => Some(Self::default()),
+            "fast" => Some(Self::fast()),
+            "accurate" => Some(Self::accurate()),
+            _ => None,
+        }
+    }
+}
+
+impl Default for DecodingOptions {
+    /// Valores por defecto de whisper.cpp
+    fn default() -> Self {
+        Self {
+            strategy: DecodingStrategy::Greedy { best_of: 1 },
+            temperature: 0.0,
+            temperature_inc: 0.2,
+            entropy_threshold: 2.4,
+            logprob_threshold: -1.0,
+            no_speech_threshold: 0.6,
+        }
+    }
+}
+
+/// Acepta el nombre de un perfil ("accurate") o los campos sueltos, que
+/// completan el perfil por defecto
+impl<'de> Deserialize<'de> for DecodingOptions {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Default, Deserialize)]
+        #[serde(default)]
+        struct Fields {
+            preset: Option<String>,
+            strategy: Option<DecodingStrategy>,
+            temperature: Option<f32>,
+            temperature_inc: Option<f32>,
+            entropy_threshold: Option<f32>,
+            logprob_threshold: Option<f32>,
+            no_speech_threshold: Option<f32>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Preset(String),
+            Fields(Fields),
+        }
+
+        let unknown = |name: &str| serde::de::Error::custom(format!("unknown decoding preset: {}", name));
+        match Repr::deserialize(deserializer)? {
+            Repr::Preset(name) => DecodingOptions::preset(&name).ok_or_else(|| unknown(&name)),
+            Repr::Fields(fields) => {
+                let base = match fields.preset {
+                    Some(name) => DecodingOptions::preset(&name).ok_or_else(|| unknown(&name))?,
+                    None => DecodingOptions::default(),
+                };
+                Ok(DecodingOptions {
+                    strategy: fields.strategy.unwrap_or(base.strategy),
+                    temperature: fields.temperature.unwrap_or(base.temperature),
+                    temperature_inc: fields.temperature_inc.unwrap_or(base.temperature_inc),
+                    entropy_threshold: fields.entropy_threshold.unwrap_or(base.entropy_threshold),
+                    logprob_threshold: fields.logprob_threshold.unwrap_or(base.logprob_threshold),
+                    no_speech_threshold: fields.no_speech_threshold.unwrap_or(base.no_speech_threshold),
+                })
+            }
+        }
+    }
+}
+
+/// Qué hacer con los segmentos que el filtro considera alucinaciones
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum HallucinationFilterMode {
+    /// Sin filtro
+    #[default]
+    Off,
+    /// Conservar los segmentos, marcados con el motivo
+    Flag,
+    /// Eliminar los segmentos del resultado
+    Drop,
+}
+
+/// Filtro de alucinaciones aplicado tras la inferencia
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct HallucinationFilter {
+    pub mode: HallucinationFilterMode,
+    /// Segmentos con más probabilidad de silencio que esta se consideran sin voz
+    pub no_speech_threshold: f32,
+    /// Segmentos con log-probabilidad media por debajo de esta se consideran poco fiables
+    pub logprob_threshold: f32,
+    /// Repeticiones consecutivas a partir de las cuales un texto se considera un bucle
+    pub min_repeats: usize,
+}
+
+impl Default for HallucinationFilter {
+    fn default() -> Self {
+        Self {
+            mode: HallucinationFilterMode::Off,
+            no_speech_threshold: 0.8,
+            logprob_threshold: -1.0,
+            min_repeats: 3,
+        }
+    }
+}
+
+/// Opciones de transcripción
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscribeOptions {
+    pub model: String,
+    pub language: String,
+    #[serde(default)]
+    pub include_timestamps: bool,
+    /// Calcular tiempos y confianza por palabra a partir de los tokens de Whisper
+    #[serde(default)]
+    pub word_timestamps: bool,
+    /// Factor de velocidad del audio (1.0 = normal, 1.5 = 1.5x más rápido, máx. 2.0)
+    #[serde(default = "default_speed")]
+    pub speed: f32,
+    /// Transcribir en el idioma hablado o traducir al inglés
+    #[serde(default)]
+    pub task: Task,
+    /// Saltar el silencio: solo se envían a Whisper las regiones con voz
+    #[serde(default)]
+    pub vad: bool,
+    /// Segundos de audio repetidos entre chunks consecutivos de archivos largos (0 = sin solape)
+    #[serde(default)]
+    pub chunk_overlap_secs: f32,
+    /// Prompt inicial o glosario del dominio (nombres de productos, jerga)
+    #[serde(default)]
+    pub initial_prompt: Option<String>,
+    /// Con idioma automático, detectar el idioma de cada chunk (grabaciones que mezclan idiomas)
+    #[serde(default)]
+    pub language_per_chunk: bool,
+    /// Perfil de decodificación: el nombre de un perfil ("fast", "accurate") o ajustes sueltos
+    #[serde(default)]
+    pub decoding: DecodingOptions,
+    /// Eliminar o marcar bucles y segmentos que Whisper inventa sobre silencio
+    #[serde(default)]
+    pub hallucination_filter: HallucinationFilter,
+}
+
+fn default_speed() -> f32 {
+    1.0
+}
+
+impl Default for TranscribeOptions {
     fn default() -> Self {
-        Language::Auto
+        Self {
+            model: "base".to_string(),
+            language: "auto".to_string(),
+            include_timestamps: false,
+            word_timestamps: false,
+            speed: 1.0,
+            task: Task::Transcribe,
+            vad: false,
+            chunk_overlap_secs: 0.0,
+            initial_prompt: None,
+            language_per_chunk: false,
+            decoding: DecodingOptions::default(),
+            hallucination_filter: HallucinationFilter::default(),
+        }
     }
 }
 
@@ -174,3 +633,77 @@ pub const VIDEO_FORMATS: &[&str] = &["mp4", "avi", "mov"];
 pub const WHISPER_SAMPLE_RATE: u32 = 16000;
 pub const CHUNK_DURATION_SECS: f32 = 60.0;
 pub const LARGE_FILE_THRESHOLD_SECS: f32 = 120.0;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_catalog() {
+        assert_eq!(WhisperModel::from_name("Base"), Some(WhisperModel::BASE));
+        assert_eq!(WhisperModel::LARGE_V3_TURBO.to_string(), "large-v3-turbo");
+        assert_eq!(WhisperModel::TINY.filename(), "ggml-tiny.bin");
+        assert_eq!(WhisperModel::SMALL.filename(), "ggml-small.bin");
+        assert_eq!(WhisperModel::MEDIUM.filename(), "ggml-medium.bin");
+        assert_eq!(WhisperModel::LARGE.filename(), "ggml-large.bin");
+
+        let english = WhisperModel::from_name("small.en-q8_0").unwrap();
+        assert!(english.is_english_only());
+        assert_eq!(english.spec().unwrap().quantization, Some("q8_0"));
+        assert!(WhisperModel::from_name("small-q4_0").is_none());
+
+        for spec in MODEL_CATALOG {
+            assert_eq!(spec.filename, format!("ggml-{}.bin", spec.name));
+            assert_eq!(spec.english_only, spec.name.contains(".en"));
+        }
+    }
+
+    #[test]
+    fn test_catalog_checksums_are_sha256() {
+        for spec in MODEL_CATALOG {
+            if let Some(sha256) = spec.sha256 {
+                assert_eq!(sha256.len(), 64, "{}", spec.name);
+                assert!(
+                    sha256.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b)),
+                    "{}",
+                    spec.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_download_url_from_mirror() {
+        assert_eq!(
+            WhisperModel::BASE.download_url_from("http://mirror.local/whisper/"),
+            Some("http://mirror.local/whisper/ggml-base.bin".to_string())
+        );
+        assert_eq!(
+            WhisperModel::TINY.download_url_from("file:///mnt/models"),
+            Some("file:///mnt/models/ggml-tiny.bin".to_string())
+        );
+    }
+
+    #[test]
+    fn test_decoding_options_from_preset_name() {
+        let options: DecodingOptions = serde_json::from_str("\"accurate\"").unwrap();
+        assert_eq!(options, DecodingOptions::accurate());
+        assert!(serde_json::from_str::<DecodingOptions>("\"slow\"").is_err());
+    }
+
+    #[test]
+    fn test_decoding_options_fields_override_preset() {
+        let options: DecodingOptions = serde_json::from_str(
+            r#"{"preset": "fast", "strategy": {"type": "beam", "beam_size": 3}, "temperature": 0.4}"#,
+        )
+        .unwrap();
+        assert_eq!(options.strategy, DecodingStrategy::Beam { beam_size: 3 });
+        assert_eq!(options.temperature, 0.4);
+        assert_eq!(options.temperature_inc, 0.0);
+        assert_eq!(options.no_speech_threshold, DecodingOptions::default().no_speech_threshold);
+
+        let options: TranscribeOptions =
+            serde_json::from_str(r#"{"model": "base", "language": "auto"}"#).unwrap();
+        assert_eq!(options.decoding, DecodingOptions::default());
+    }
+}
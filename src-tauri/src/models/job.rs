@@ -0,0 +1,84 @@
+use super::TranscribeOptions;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Estado de un job de la cola de transcripción
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    /// Indica si el job ya terminó (con o sin éxito)
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobStatus::Done | JobStatus::Failed | JobStatus::Cancelled)
+    }
+}
+
+/// Origen del audio de un job
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JobSource {
+    /// Archivo local de audio o video
+    File { path: String },
+    /// URL (YouTube u otra fuente soportada por yt-dlp)
+    Url { url: String },
+}
+
+impl JobSource {
+    /// Interpreta una entrada del usuario como URL o como ruta de archivo
+    pub fn parse(input: &str) -> Self {
+        let input = input.trim();
+        if input.starts_with("http://") || input.starts_with("https://") {
+            JobSource::Url {
+                url: input.to_string(),
+            }
+        } else {
+            JobSource::File {
+                path: input.to_string(),
+            }
+        }
+    }
+}
+
+/// Job de la cola de transcripción
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionJob {
+    /// ID único del job (también usado con `cancel_transcription`)
+    pub id: String,
+    /// Origen del audio
+    pub source: JobSource,
+    /// Opciones de transcripción
+    pub options: TranscribeOptions,
+    /// Estado actual
+    pub status: JobStatus,
+    /// Fecha de creación
+    pub created_at: DateTime<Utc>,
+    /// Inicio del procesamiento
+    pub started_at: Option<DateTime<Utc>>,
+    /// Fin del procesamiento
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Mensaje de error si el job falló
+    pub error: Option<String>,
+}
+
+impl TranscriptionJob {
+    /// Crea un job nuevo en estado `Queued`
+    pub fn new(source: JobSource, options: TranscribeOptions) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            source,
+            options,
+            status: JobStatus::Queued,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+            error: None,
+        }
+    }
+}
@@ -1,5 +1,9 @@
 pub mod config;
+pub mod job;
+pub mod settings;
 pub mod transcription;
 
 pub use config::*;
+pub use job::*;
+pub use settings::*;
 pub use transcription::*;
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Ajustes de la aplicación que persisten entre sesiones
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AppSettings {
+    /// Chunks de un archivo largo que se transcriben a la vez (1 = en serie)
+    ///
+    /// Cada worker usa su propio estado de Whisper, con su memoria. Con más de
+    /// uno, los chunks no reciben el texto del chunk anterior como prompt.
+    pub chunk_workers: usize,
+    /// Hilos de Whisper por chunk (0 = automático)
+    pub whisper_threads: usize,
+    /// Memoria para modelos cargados, en MB. Por encima se descargan los
+    /// menos usados recientemente
+    pub model_memory_budget_mb: u64,
+    /// URL base desde la que se descargan los modelos del catálogo, en lugar de
+    /// Hugging Face: un mirror HTTP interno o una carpeta (`file:///...`)
+    pub model_mirror_url: Option<String>,
+    /// Directorios de solo lectura donde buscar modelos antes de descargarlos,
+    /// p. ej. una unidad de red compartida
+    pub extra_model_dirs: Vec<PathBuf>,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            chunk_workers: 1,
+            whisper_threads: 0,
+            model_memory_budget_mb: 4096,
+            model_mirror_url: None,
+            extra_model_dirs: Vec::new(),
+        }
+    }
+}
+
+impl AppSettings {
+    /// Ajusta los valores fuera de rango a los núcleos disponibles
+    pub fn normalized(mut self) -> Self {
+        let cores = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        self.chunk_workers = self.chunk_workers.clamp(1, cores);
+        self.whisper_threads = self.whisper_threads.min(cores);
+        self.model_mirror_url = self
+            .model_mirror_url
+            .map(|url| url.trim().trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty());
+        self.extra_model_dirs.retain(|dir| !dir.as_os_str().is_empty());
+        self
+    }
+
+    /// Presupuesto de memoria para modelos en bytes
+    pub fn model_memory_budget_bytes(&self) -> u64 {
+        self.model_memory_budget_mb.saturating_mul(1024 * 1024)
+    }
+}
@@ -1,3 +1,4 @@
+use super::{HallucinationFilterMode, Task};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -32,6 +33,235 @@ impl AudioInfo {
     }
 }
 
+/// Segmento de transcripción con sus tiempos de inicio y fin
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Segment {
+    /// Inicio del segmento en milisegundos (línea de tiempo original)
+    pub start_ms: i64,
+    /// Fin del segmento en milisegundos (línea de tiempo original)
+    pub end_ms: i64,
+    /// Texto del segmento, sin espacios al inicio ni al final
+    pub text: String,
+    /// Probabilidad de que el segmento no contenga voz (0.0 - 1.0)
+    #[serde(default)]
+    pub no_speech_prob: Option<f32>,
+    /// Whisper predice un cambio de hablante tras este segmento
+    #[serde(default)]
+    pub speaker_turn_next: bool,
+    /// Palabras con tiempos y probabilidad (solo con `word_timestamps` activado)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<Word>,
+    /// Idioma en que se transcribió el segmento (solo con detección por chunk)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Media del log de la probabilidad de los tokens de texto
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_logprob: Option<f32>,
+    /// Motivo por el que el filtro marcó el segmento como alucinación (modo `flag`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hallucination: Option<HallucinationReason>,
+}
+
+/// Palabra con tiempos y probabilidad, derivada de los tokens de Whisper
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Word {
+    /// Inicio de la palabra en milisegundos
+    pub start_ms: i64,
+    /// Fin de la palabra en milisegundos
+    pub end_ms: i64,
+    /// Texto de la palabra (incluye la puntuación adjunta)
+    pub text: String,
+    /// Confianza (media de las probabilidades de sus tokens, 0.0 - 1.0)
+    pub probability: f32,
+}
+
+impl Segment {
+    /// Crea un segmento sin metadatos adicionales
+    pub fn new(start_ms: i64, end_ms: i64, text: impl Into<String>) -> Self {
+        Self {
+            start_ms,
+            end_ms,
+            text: text.into(),
+            no_speech_prob: None,
+            speaker_turn_next: false,
+            words: Vec::new(),
+            language: None,
+            avg_logprob: None,
+            hallucination: None,
+        }
+    }
+
+    /// Desplaza los tiempos del segmento y de sus palabras
+    pub fn offset_by(&mut self, offset_ms: i64) {
+        self.start_ms += offset_ms;
+        self.end_ms += offset_ms;
+        for word in self.words.iter_mut() {
+            word.start_ms += offset_ms;
+            word.end_ms += offset_ms;
+        }
+    }
+
+    /// Escala los tiempos del segmento y de sus palabras (p. ej. para compensar audio acelerado)
+    pub fn scale_by(&mut self, factor: f32) {
+        let scale = |ms: i64| ((ms as f64) * (factor as f64)).round() as i64;
+        self.start_ms = scale(self.start_ms);
+        self.end_ms = scale(self.end_ms);
+        for word in self.words.iter_mut() {
+            word.start_ms = scale(word.start_ms);
+            word.end_ms = scale(word.end_ms);
+        }
+    }
+
+    /// Aplica una transformación arbitraria a los tiempos del segmento y de sus palabras
+    pub fn map_times(&mut self, map: impl Fn(i64) -> i64) {
+        self.start_ms = map(self.start_ms);
+        self.end_ms = map(self.end_ms);
+        for word in self.words.iter_mut() {
+            word.start_ms = map(word.start_ms);
+            word.end_ms = map(word.end_ms);
+        }
+    }
+}
+
+/// Tramo continuo de audio en un mismo idioma
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LanguageSpan {
+    /// Inicio del tramo en milisegundos
+    pub start_ms: i64,
+    /// Fin del tramo en milisegundos
+    pub end_ms: i64,
+    /// Código ISO del idioma
+    pub language: String,
+}
+
+/// Agrupa los segmentos consecutivos con el mismo idioma en tramos
+/// Los segmentos sin idioma se ignoran
+pub fn language_spans(segments: &[Segment]) -> Vec<LanguageSpan> {
+    let mut spans: Vec<LanguageSpan> = Vec::new();
+
+    for segment in segments {
+        let Some(ref language) = segment.language else {
+            continue;
+        };
+        match spans.last_mut() {
+            Some(span) if span.language == *language => span.end_ms = segment.end_ms,
+            _ => spans.push(LanguageSpan {
+                start_ms: segment.start_ms,
+                end_ms: segment.end_ms,
+                language: language.clone(),
+            }),
+        }
+    }
+
+    spans
+}
+
+/// Idioma con más duración total en los tramos
+pub fn dominant_language(spans: &[LanguageSpan]) -> Option<String> {
+    let mut totals: Vec<(&str, i64)> = Vec::new();
+    for span in spans {
+        let duration = span.end_ms - span.start_ms;
+        match totals.iter_mut().find(|(language, _)| *language == span.language) {
+            Some((_, total)) => *total += duration,
+            None => totals.push((&span.language, duration)),
+        }
+    }
+
+    // A igual duración gana el que aparece antes
+    totals
+        .iter()
+        .rev()
+        .max_by_key(|(_, total)| *total)
+        .map(|(language, _)| language.to_string())
+}
+
+/// Motivo por el que un segmento se considera una alucinación de Whisper
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HallucinationReason {
+    /// Repite en bucle el texto anterior
+    Repetition,
+    /// Whisper estima que no hay voz
+    NoSpeech,
+    /// Probabilidad media de los tokens demasiado baja
+    LowConfidence,
+}
+
+/// Segmento detectado por el filtro de alucinaciones
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FilteredSegment {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub text: String,
+    pub reason: HallucinationReason,
+}
+
+/// Informe del filtro de alucinaciones: qué se eliminó o marcó y por qué
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HallucinationReport {
+    /// `drop` si los segmentos se eliminaron del resultado, `flag` si solo se marcaron
+    pub mode: HallucinationFilterMode,
+    pub segments: Vec<FilteredSegment>,
+}
+
+impl HallucinationReport {
+    /// Escala los tiempos del informe (p. ej. para compensar audio acelerado)
+    pub fn scale_by(&mut self, factor: f32) {
+        let scale = |ms: i64| ((ms as f64) * (factor as f64)).round() as i64;
+        for segment in self.segments.iter_mut() {
+            segment.start_ms = scale(segment.start_ms);
+            segment.end_ms = scale(segment.end_ms);
+        }
+    }
+}
+
+/// Renderiza los segmentos como texto plano
+pub fn render_plain_text(segments: &[Segment]) -> String {
+    segments
+        .iter()
+        .map(|s| s.text.trim())
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Renderiza los segmentos con marcas de tiempo `[HH:MM:SS]`, una línea por segmento
+pub fn render_timestamped_text(segments: &[Segment]) -> String {
+    segments
+        .iter()
+        .filter(|s| !s.text.trim().is_empty())
+        .map(|s| format!("[{}] {}", format_timestamp_hms(s.start_ms), s.text.trim()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Renderiza los segmentos con o sin marcas de tiempo
+pub fn render_segments(segments: &[Segment], include_timestamps: bool) -> String {
+    if include_timestamps {
+        render_timestamped_text(segments)
+    } else {
+        render_plain_text(segments)
+    }
+}
+
+/// Formatea milisegundos a formato HH:MM:SS
+fn format_timestamp_hms(ms: i64) -> String {
+    let total_seconds = ms.max(0) / 1000;
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let seconds = total_seconds % 60;
+    format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+}
+
+/// Probabilidad de un idioma según la detección de Whisper
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LanguageProbability {
+    /// Código ISO del idioma
+    pub code: String,
+    /// Probabilidad (0.0 - 1.0)
+    pub probability: f32,
+}
+
 /// Tipo de fuente de la transcripción
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -80,6 +310,21 @@ pub struct TranscriptionEntry {
     pub char_count: usize,
     /// Idioma detectado
     pub detected_language: Option<String>,
+    /// Segmentos con tiempos (vacío para subtítulos de YouTube y entradas antiguas)
+    #[serde(default)]
+    pub segments: Vec<Segment>,
+    /// Transcripción en el idioma original o traducción al inglés
+    #[serde(default)]
+    pub task: Task,
+    /// Idiomas candidatos de la detección automática (vacío si el idioma se fijó)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub language_probabilities: Vec<LanguageProbability>,
+    /// Tramos por idioma (solo con detección de idioma por chunk)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub language_spans: Vec<LanguageSpan>,
+    /// Segmentos eliminados o marcados por el filtro de alucinaciones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hallucinations: Option<HallucinationReport>,
 }
 
 impl TranscriptionEntry {
@@ -94,7 +339,8 @@ impl TranscriptionEntry {
     ) -> Self {
         let word_count = transcription.split_whitespace().count();
         let char_count = transcription.chars().count();
-        let id = chrono::Utc::now().format("%Y%m%d_%H%M%S").to_string();
+        // Incluye milisegundos: la cola puede terminar varios jobs en el mismo segundo
+        let id = chrono::Utc::now().format("%Y%m%d_%H%M%S_%3f").to_string();
 
         Self {
             id,
@@ -107,8 +353,35 @@ impl TranscriptionEntry {
             word_count,
             char_count,
             detected_language,
+            segments: Vec::new(),
+            task: Task::Transcribe,
+            language_probabilities: Vec::new(),
+            language_spans: Vec::new(),
+            hallucinations: None,
         }
     }
+
+    /// Crea una entrada a partir del resultado de una transcripción con Whisper
+    pub fn from_result(
+        source_name: String,
+        source_type: SourceType,
+        result: &TranscriptionResult,
+    ) -> Self {
+        let mut entry = Self::new(
+            source_name,
+            source_type,
+            result.text.clone(),
+            result.audio_info.clone(),
+            result.processing_time,
+            result.language.clone(),
+        );
+        entry.segments = result.segments.clone();
+        entry.task = result.task;
+        entry.language_probabilities = result.language_probabilities.clone();
+        entry.language_spans = result.language_spans.clone();
+        entry.hallucinations = result.hallucinations.clone();
+        entry
+    }
 }
 
 /// Resultado de una transcripción
@@ -122,6 +395,21 @@ pub struct TranscriptionResult {
     pub audio_info: Option<AudioInfo>,
     /// Tiempo de procesamiento en segundos
     pub processing_time: f64,
+    /// Segmentos con tiempos a partir de los cuales se renderiza `text`
+    #[serde(default)]
+    pub segments: Vec<Segment>,
+    /// Tarea realizada (transcripción o traducción al inglés)
+    #[serde(default)]
+    pub task: Task,
+    /// Idiomas candidatos de la detección automática (vacío si el idioma se fijó)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub language_probabilities: Vec<LanguageProbability>,
+    /// Tramos por idioma (solo con detección de idioma por chunk)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub language_spans: Vec<LanguageSpan>,
+    /// Segmentos eliminados o marcados por el filtro de alucinaciones (None si está desactivado)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hallucinations: Option<HallucinationReport>,
 }
 
 /// Información de un video de YouTube
@@ -143,14 +431,109 @@ pub struct VideoInfo {
 pub enum ProgressEvent {
     /// Inicio del proceso
     Started { message: String },
-    /// Progreso de transcripción (0.0 - 1.0)
-    Progress { progress: f32, message: String },
+    /// Progreso de transcripción (0.0 - 1.0), con el texto del chunk recién terminado
+    Progress {
+        progress: f32,
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        chunk_text: Option<String>,
+    },
     /// Procesando chunk N de M
     ChunkProgress { current: u32, total: u32 },
     /// Descarga de modelo en progreso
     ModelDownload { progress: f32, bytes_downloaded: u64, total_bytes: u64 },
     /// Proceso completado
     Completed { message: String },
+    /// Proceso cancelado por el usuario
+    Cancelled { message: String },
     /// Error durante el proceso
     Error { message: String },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_segments() -> Vec<Segment> {
+        vec![
+            Segment::new(0, 4_200, " Hello there."),
+            Segment::new(4_200, 9_800, "General Kenobi."),
+            Segment::new(65_000, 70_000, "  "),
+        ]
+    }
+
+    #[test]
+    fn test_render_plain_text() {
+        assert_eq!(
+            render_plain_text(&sample_segments()),
+            "Hello there. General Kenobi."
+        );
+    }
+
+    #[test]
+    fn test_render_timestamped_text() {
+        assert_eq!(
+            render_timestamped_text(&sample_segments()),
+            "[00:00:00] Hello there.\n[00:00:04] General Kenobi."
+        );
+    }
+
+    #[test]
+    fn test_segment_scale_and_offset() {
+        let mut segment = Segment::new(60_000, 61_000, "text");
+        segment.scale_by(1.5);
+        assert_eq!((segment.start_ms, segment.end_ms), (90_000, 91_500));
+
+        segment.offset_by(500);
+        assert_eq!((segment.start_ms, segment.end_ms), (90_500, 92_000));
+    }
+
+    #[test]
+    fn test_entry_records_task() {
+        let result = TranscriptionResult {
+            text: "Good morning".to_string(),
+            language: Some("es".to_string()),
+            audio_info: None,
+            processing_time: 1.0,
+            segments: Vec::new(),
+            task: Task::Translate,
+            language_probabilities: Vec::new(),
+            language_spans: Vec::new(),
+            hallucinations: None,
+        };
+        let entry = TranscriptionEntry::from_result("meeting".to_string(), SourceType::Whisper, &result);
+        assert_eq!(entry.task, Task::Translate);
+
+        // Las entradas guardadas antes de existir el campo son transcripciones
+        let mut json = serde_json::to_value(&entry).unwrap();
+        json.as_object_mut().unwrap().remove("task");
+        let old: TranscriptionEntry = serde_json::from_value(json).unwrap();
+        assert_eq!(old.task, Task::Transcribe);
+    }
+
+    #[test]
+    fn test_language_spans() {
+        let segment = |start, end, language: &str| {
+            let mut segment = Segment::new(start, end, "text");
+            segment.language = Some(language.to_string());
+            segment
+        };
+        let segments = vec![
+            segment(0, 10_000, "en"),
+            segment(10_000, 20_000, "en"),
+            segment(20_000, 50_000, "es"),
+            Segment::new(50_000, 55_000, "sin idioma"),
+            segment(55_000, 60_000, "en"),
+        ];
+
+        let spans = language_spans(&segments);
+        assert_eq!(spans.len(), 3);
+        assert_eq!((spans[0].start_ms, spans[0].end_ms), (0, 20_000));
+        assert_eq!(spans[1].language, "es");
+        assert_eq!((spans[2].start_ms, spans[2].end_ms), (55_000, 60_000));
+
+        assert_eq!(dominant_language(&spans), Some("es".to_string()));
+        assert_eq!(dominant_language(&spans[..1]), Some("en".to_string()));
+        assert_eq!(dominant_language(&[]), None);
+    }
+}
@@ -15,23 +15,20 @@ pub struct HistoryManager {
 impl HistoryManager {
     /// Crea un nuevo manager de historial
     pub fn new() -> Self {
-        let (history_file, transcriptions_dir) =
-            if let Some(proj_dirs) = ProjectDirs::from("com", "audioink", "AudioInk") {
-                let data_dir = proj_dirs.data_dir();
-                (
-                    data_dir.join("history.json"),
-                    data_dir.join("transcriptions"),
-                )
-            } else {
-                (
-                    PathBuf::from("./history.json"),
-                    PathBuf::from("./transcriptions"),
-                )
-            };
+        let data_dir = if let Some(proj_dirs) = ProjectDirs::from("com", "audioink", "AudioInk") {
+            proj_dirs.data_dir().to_path_buf()
+        } else {
+            PathBuf::from(".")
+        };
+
+        Self::with_dir(data_dir)
+    }
 
+    /// Crea un manager que guarda el historial en un directorio concreto
+    pub fn with_dir(data_dir: PathBuf) -> Self {
         Self {
-            history_file,
-            transcriptions_dir,
+            history_file: data_dir.join("history.json"),
+            transcriptions_dir: data_dir.join("transcriptions"),
         }
     }
 
@@ -180,6 +177,11 @@ impl HistoryManager {
         Ok(history.len())
     }
 
+    /// Obtiene la ruta del archivo de historial
+    pub fn history_file(&self) -> &PathBuf {
+        &self.history_file
+    }
+
     /// Obtiene la ruta del directorio de transcripciones
     pub fn transcriptions_dir(&self) -> &PathBuf {
         &self.transcriptions_dir
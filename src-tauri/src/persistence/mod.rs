@@ -1,3 +1,7 @@
 pub mod history;
+pub mod queue;
+pub mod settings;
 
 pub use history::*;
+pub use queue::*;
+pub use settings::*;
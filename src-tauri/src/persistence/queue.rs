@@ -0,0 +1,351 @@
+use crate::models::{JobStatus, TranscriptionJob};
+use crate::utils::{AudioInkError, AudioInkResult};
+use chrono::Utc;
+use directories::ProjectDirs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::fs;
+use tokio::sync::{Mutex, Notify};
+
+/// Cola persistente de jobs de transcripción
+///
+/// Los jobs se guardan en `queue.json` tras cada cambio, de modo que la cola
+/// sobrevive a un reinicio de la aplicación. Los jobs que estaban en ejecución
+/// al cerrar vuelven a `Queued` al restaurar.
+///
+/// La cola guardada se carga antes del primer cambio aunque `restore` aún no
+/// se haya llamado, para que un job añadido al arrancar no sobrescriba
+/// `queue.json`.
+pub struct QueueManager {
+    queue_file: PathBuf,
+    jobs: Mutex<Vec<TranscriptionJob>>,
+    /// Si la cola guardada ya se cargó (o se apartó por estar dañada)
+    restored: AtomicBool,
+    job_available: Notify,
+}
+
+impl QueueManager {
+    /// Crea un nuevo manager de la cola
+    pub fn new() -> Self {
+        let queue_file = if let Some(proj_dirs) = ProjectDirs::from("com", "audioink", "AudioInk") {
+            proj_dirs.data_dir().join("queue.json")
+        } else {
+            PathBuf::from("./queue.json")
+        };
+
+        Self::with_file(queue_file)
+    }
+
+    /// Crea un manager que persiste en un archivo concreto
+    pub fn with_file(queue_file: PathBuf) -> Self {
+        Self {
+            queue_file,
+            jobs: Mutex::new(Vec::new()),
+            restored: AtomicBool::new(false),
+            job_available: Notify::new(),
+        }
+    }
+
+    /// Carga la cola desde disco
+    ///
+    /// Los jobs guardados van delante de los que se hayan añadido antes de
+    /// restaurar. Si `queue.json` no se puede leer, se aparta como
+    /// `queue.json.corrupt-<fecha>` y se devuelve el error.
+    pub async fn restore(&self) -> AudioInkResult<()> {
+        let mut jobs = self.jobs.lock().await;
+        self.ensure_restored(&mut jobs).await?;
+
+        if jobs.iter().any(|j| j.status == JobStatus::Queued) {
+            self.job_available.notify_one();
+        }
+
+        Ok(())
+    }
+
+    /// Añade jobs al final de la cola
+    pub async fn enqueue(&self, new_jobs: Vec<TranscriptionJob>) -> AudioInkResult<Vec<TranscriptionJob>> {
+        let mut jobs = self.jobs.lock().await;
+        self.ensure_restored(&mut jobs).await?;
+        jobs.extend(new_jobs);
+        self.persist(&jobs).await?;
+        self.job_available.notify_one();
+        Ok(jobs.clone())
+    }
+
+    /// Lista todos los jobs en orden de procesamiento
+    pub async fn list(&self) -> Vec<TranscriptionJob> {
+        self.jobs.lock().await.clone()
+    }
+
+    /// Obtiene un job por ID
+    pub async fn get(&self, id: &str) -> Option<TranscriptionJob> {
+        self.jobs.lock().await.iter().find(|j| j.id == id).cloned()
+    }
+
+    /// Mueve un job a una nueva posición de la cola
+    /// Devuelve false si el job no existe
+    pub async fn move_job(&self, id: &str, position: usize) -> AudioInkResult<bool> {
+        let mut jobs = self.jobs.lock().await;
+        self.ensure_restored(&mut jobs).await?;
+
+        let Some(current) = jobs.iter().position(|j| j.id == id) else {
+            return Ok(false);
+        };
+
+        let job = jobs.remove(current);
+        let position = position.min(jobs.len());
+        jobs.insert(position, job);
+
+        self.persist(&jobs).await?;
+        Ok(true)
+    }
+
+    /// Elimina un job de la cola y lo devuelve
+    pub async fn remove(&self, id: &str) -> AudioInkResult<Option<TranscriptionJob>> {
+        let mut jobs = self.jobs.lock().await;
+        self.ensure_restored(&mut jobs).await?;
+
+        let Some(index) = jobs.iter().position(|j| j.id == id) else {
+            return Ok(None);
+        };
+
+        let job = jobs.remove(index);
+        self.persist(&jobs).await?;
+        Ok(Some(job))
+    }
+
+    /// Marca como `Running` el primer job en espera y lo devuelve
+    ///
+    /// `before_start` se llama con la cola bloqueada antes de cambiar el estado,
+    /// para registrar el token de cancelación antes de que `remove` pueda ver el
+    /// job en ejecución. Si no se puede guardar la cola, el job vuelve a su
+    /// estado anterior.
+    pub async fn start_next(
+        &self,
+        before_start: impl FnOnce(&TranscriptionJob),
+    ) -> AudioInkResult<Option<TranscriptionJob>> {
+        let mut jobs = self.jobs.lock().await;
+        self.ensure_restored(&mut jobs).await?;
+
+        let Some(index) = jobs.iter().position(|j| j.status == JobStatus::Queued) else {
+            return Ok(None);
+        };
+
+        before_start(&jobs[index]);
+
+        let previous = jobs[index].clone();
+        let job = &mut jobs[index];
+        job.status = JobStatus::Running;
+        job.started_at = Some(Utc::now());
+        job.error = None;
+        let job = job.clone();
+
+        if let Err(e) = self.persist(&jobs).await {
+            jobs[index] = previous;
+            return Err(e);
+        }
+        Ok(Some(job))
+    }
+
+    /// Registra el resultado de un job
+    /// No hace nada si el job fue eliminado mientras se ejecutaba
+    pub async fn finish(&self, id: &str, status: JobStatus, error: Option<String>) -> AudioInkResult<()> {
+        let mut jobs = self.jobs.lock().await;
+
+        if let Some(job) = jobs.iter_mut().find(|j| j.id == id) {
+            job.status = status;
+            job.error = error;
+            job.finished_at = Some(Utc::now());
+            self.persist(&jobs).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Espera hasta que se añadan jobs a la cola
+    pub async fn wait_for_jobs(&self) {
+        self.job_available.notified().await;
+    }
+
+    /// Carga los jobs guardados delante de `jobs` si aún no se cargaron
+    async fn ensure_restored(&self, jobs: &mut Vec<TranscriptionJob>) -> AudioInkResult<()> {
+        if self.restored.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let mut saved: Vec<TranscriptionJob> = if self.queue_file.exists() {
+            let content = fs::read_to_string(&self.queue_file).await?;
+            match serde_json::from_str(&content) {
+                Ok(saved) => saved,
+                Err(e) => {
+                    // Apartar el archivo para no sobrescribirlo con la cola vacía
+                    let backup = self.queue_file.with_extension(format!(
+                        "json.corrupt-{}",
+                        Utc::now().format("%Y%m%d%H%M%S")
+                    ));
+                    fs::rename(&self.queue_file, &backup).await?;
+                    self.restored.store(true, Ordering::SeqCst);
+                    return Err(AudioInkError::Persistence(format!(
+                        "La cola guardada no se pudo leer ({}); se movió a {}",
+                        e,
+                        backup.display()
+                    )));
+                }
+            }
+        } else {
+            Vec::new()
+        };
+
+        // Un job "running" en disco fue interrumpido por el cierre de la app
+        for job in saved.iter_mut() {
+            if job.status == JobStatus::Running {
+                job.status = JobStatus::Queued;
+                job.started_at = None;
+            }
+        }
+
+        let pending: Vec<TranscriptionJob> = std::mem::take(jobs)
+            .into_iter()
+            .filter(|job| !saved.iter().any(|s| s.id == job.id))
+            .collect();
+        saved.extend(pending);
+        *jobs = saved;
+        self.persist(jobs).await?;
+        self.restored.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Guarda la cola en disco (escritura atómica vía archivo temporal)
+    async fn persist(&self, jobs: &[TranscriptionJob]) -> AudioInkResult<()> {
+        if let Some(parent) = self.queue_file.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let json = serde_json::to_string_pretty(jobs)?;
+        let temp_path = self.queue_file.with_extension("json.tmp");
+        fs::write(&temp_path, json).await?;
+        fs::rename(&temp_path, &self.queue_file).await?;
+        Ok(())
+    }
+}
+
+impl Default for QueueManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{JobSource, TranscribeOptions};
+
+    fn job(path: &str) -> TranscriptionJob {
+        TranscriptionJob::new(JobSource::parse(path), TranscribeOptions::default())
+    }
+
+    fn ids(jobs: &[TranscriptionJob]) -> Vec<String> {
+        jobs.iter().map(|j| j.id.clone()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_queue_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("queue.json");
+
+        let queue = QueueManager::with_file(file.clone());
+        let jobs = queue.enqueue(vec![job("/a.mp3"), job("/b.mp3")]).await.unwrap();
+        let running = queue.start_next(|_| ()).await.unwrap().unwrap();
+        assert_eq!(running.id, jobs[0].id);
+
+        // Simula un reinicio con el primer job a medias
+        let restored = QueueManager::with_file(file);
+        restored.restore().await.unwrap();
+        let restored_jobs = restored.list().await;
+
+        assert_eq!(ids(&restored_jobs), ids(&jobs));
+        assert!(restored_jobs.iter().all(|j| j.status == JobStatus::Queued));
+    }
+
+    #[tokio::test]
+    async fn test_jobs_enqueued_before_restore_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("queue.json");
+
+        let saved = QueueManager::with_file(file.clone());
+        let saved_jobs = saved.enqueue(vec![job("/a.mp3")]).await.unwrap();
+
+        let queue = QueueManager::with_file(file);
+        let early = queue.enqueue(vec![job("/b.mp3")]).await.unwrap();
+        queue.restore().await.unwrap();
+
+        assert_eq!(early.len(), 2);
+        assert_eq!(
+            ids(&queue.list().await),
+            vec![saved_jobs[0].id.clone(), early[1].id.clone()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_queue_is_moved_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("queue.json");
+        std::fs::write(&file, "{ not json").unwrap();
+
+        let queue = QueueManager::with_file(file.clone());
+        assert!(matches!(queue.restore().await, Err(AudioInkError::Persistence(_))));
+
+        let backups: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with("queue.json.corrupt-"))
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(std::fs::read_to_string(backups[0].path()).unwrap(), "{ not json");
+
+        // La cola sigue funcionando sin tocar la copia apartada
+        queue.enqueue(vec![job("/a.mp3")]).await.unwrap();
+        assert_eq!(queue.list().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_start_next_registers_before_running() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = QueueManager::with_file(dir.path().join("queue.json"));
+        let jobs = queue.enqueue(vec![job("/a.mp3")]).await.unwrap();
+
+        let mut seen = None;
+        let started = queue
+            .start_next(|job| seen = Some((job.id.clone(), job.status)))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(seen, Some((jobs[0].id.clone(), JobStatus::Queued)));
+        assert_eq!(started.status, JobStatus::Running);
+    }
+
+    #[tokio::test]
+    async fn test_move_and_remove_jobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = QueueManager::with_file(dir.path().join("queue.json"));
+
+        let jobs = queue
+            .enqueue(vec![job("/a.mp3"), job("/b.mp3"), job("https://youtu.be/x")])
+            .await
+            .unwrap();
+
+        assert!(queue.move_job(&jobs[2].id, 0).await.unwrap());
+        assert_eq!(
+            ids(&queue.list().await),
+            vec![jobs[2].id.clone(), jobs[0].id.clone(), jobs[1].id.clone()]
+        );
+
+        let next = queue.start_next(|_| ()).await.unwrap().unwrap();
+        assert_eq!(next.source, JobSource::Url { url: "https://youtu.be/x".to_string() });
+
+        assert!(queue.remove(&jobs[0].id).await.unwrap().is_some());
+        assert!(!queue.move_job(&jobs[0].id, 0).await.unwrap());
+        assert_eq!(queue.list().await.len(), 2);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::models::AppSettings;
+use crate::utils::{AudioInkError, AudioInkResult};
+use directories::ProjectDirs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use tokio::fs;
+
+/// Ajustes persistentes en `settings.json`
+///
+/// Se leen al crear el manager, así el pipeline puede consultarlos sin
+/// esperar a disco. Un archivo ilegible se ignora y se usan los valores por defecto.
+pub struct SettingsManager {
+    /// None para ajustes que solo viven en memoria (p. ej. los flags del CLI)
+    settings_file: Option<PathBuf>,
+    settings: RwLock<AppSettings>,
+}
+
+impl SettingsManager {
+    /// Crea el manager con los ajustes guardados en el directorio de datos
+    pub fn new() -> Self {
+        let settings_file = if let Some(proj_dirs) = ProjectDirs::from("com", "audioink", "AudioInk") {
+            proj_dirs.data_dir().join("settings.json")
+        } else {
+            PathBuf::from("./settings.json")
+        };
+
+        Self::with_file(settings_file)
+    }
+
+    /// Crea un manager que persiste en un archivo concreto
+    pub fn with_file(settings_file: PathBuf) -> Self {
+        let settings = std::fs::read_to_string(&settings_file)
+            .ok()
+            .and_then(|content| serde_json::from_str::<AppSettings>(&content).ok())
+            .unwrap_or_default()
+            .normalized();
+
+        Self {
+            settings_file: Some(settings_file),
+            settings: RwLock::new(settings),
+        }
+    }
+
+    /// Crea un manager que no lee ni escribe en disco
+    pub fn in_memory(settings: AppSettings) -> Self {
+        Self {
+            settings_file: None,
+            settings: RwLock::new(settings.normalized()),
+        }
+    }
+
+    /// Ajustes actuales
+    pub fn get(&self) -> AppSettings {
+        self.settings
+            .read()
+            .map(|settings| settings.clone())
+            .unwrap_or_default()
+    }
+
+    /// Reemplaza los ajustes y los guarda. Devuelve los valores aplicados
+    pub async fn update(&self, settings: AppSettings) -> AudioInkResult<AppSettings> {
+        let settings = settings.normalized();
+
+        if let Some(ref settings_file) = self.settings_file {
+            if let Some(parent) = settings_file.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            let json = serde_json::to_string_pretty(&settings)?;
+            let temp_path = settings_file.with_extension("json.tmp");
+            fs::write(&temp_path, json).await?;
+            fs::rename(&temp_path, settings_file).await?;
+        }
+
+        let mut current = self
+            .settings
+            .write()
+            .map_err(|e| AudioInkError::Internal(format!("Error de lock: {}", e)))?;
+        *current = settings.clone();
+        Ok(settings)
+    }
+}
+
+impl Default for SettingsManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_settings_persist_and_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("settings.json");
+
+        let manager = SettingsManager::with_file(file.clone());
+        assert_eq!(manager.get(), AppSettings::default());
+
+        let saved = manager
+            .update(AppSettings {
+                chunk_workers: 0,
+                whisper_threads: 1,
+                ..AppSettings::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(saved.chunk_workers, 1, "workers are at least one");
+
+        let reloaded = SettingsManager::with_file(file);
+        assert_eq!(reloaded.get(), saved);
+    }
+}
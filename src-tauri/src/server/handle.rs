@@ -0,0 +1,87 @@
+//! Ciclo de vida del servidor HTTP integrado
+
+use crate::server::{router, ApiState};
+use crate::service::TranscriptionService;
+use crate::utils::{AudioInkError, AudioInkResult};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// Puerto por defecto del servidor del API
+pub const DEFAULT_SERVER_PORT: u16 = 8178;
+
+/// Ajustes del servidor HTTP
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// Dirección de escucha. Loopback salvo que el usuario decida exponerlo
+    pub host: IpAddr,
+    pub port: u16,
+    /// Modelo local para las peticiones que piden un modelo de OpenAI
+    pub default_model: String,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            host: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            port: DEFAULT_SERVER_PORT,
+            default_model: "base".to_string(),
+        }
+    }
+}
+
+/// Servidor en marcha; se detiene con [`ApiServer::stop`]
+pub struct ApiServer {
+    local_addr: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl ApiServer {
+    /// Abre el listener y atiende peticiones en el runtime de tokio actual
+    pub async fn start(config: ServerConfig, service: Arc<TranscriptionService>) -> AudioInkResult<Self> {
+        let listener = tokio::net::TcpListener::bind((config.host, config.port))
+            .await
+            .map_err(|e| {
+                AudioInkError::Network(format!(
+                    "No se pudo abrir {}:{}: {}",
+                    config.host, config.port, e
+                ))
+            })?;
+        let local_addr = listener.local_addr()?;
+
+        let app = router(ApiState {
+            service,
+            default_model: config.default_model,
+        });
+
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        let task = tokio::spawn(async move {
+            let _ = axum::serve(listener, app)
+                .with_graceful_shutdown(async {
+                    let _ = shutdown_rx.await;
+                })
+                .await;
+        });
+
+        Ok(Self {
+            local_addr,
+            shutdown,
+            task,
+        })
+    }
+
+    /// Dirección en la que escucha el servidor
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Deja de aceptar conexiones y espera a las peticiones en curso
+    pub async fn stop(self) {
+        let _ = self.shutdown.send(());
+        let _ = self.task.await;
+    }
+}
@@ -0,0 +1,5 @@
+pub mod handle;
+pub mod openai;
+
+pub use handle::*;
+pub use openai::*;
@@ -0,0 +1,530 @@
+//! Endpoints de audio compatibles con OpenAI
+//!
+//! Implementa la petición multipart y las respuestas de
+//! `POST /v1/audio/transcriptions` y `POST /v1/audio/translations` sobre el
+//! pipeline local. Las peticiones nunca se guardan en el historial.
+
+use crate::core::{is_supported_format, CancellationToken};
+use crate::models::{
+    DecodingOptions, JobSource, Segment, Task, TranscribeOptions, TranscriptionResult,
+};
+use crate::service::{parse_model, NoopReporter, TranscriptionRequest, TranscriptionService};
+use crate::utils::{segments_to_srt, segments_to_vtt, AudioInkError};
+use axum::extract::multipart::Field;
+use axum::extract::{DefaultBodyLimit, Multipart, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::NamedTempFile;
+use tokio::io::AsyncWriteExt;
+
+/// Tamaño máximo de subida. Los archivos locales suelen superar el límite de 25 MB de OpenAI
+const MAX_UPLOAD_BYTES: usize = 1024 * 1024 * 1024;
+
+/// Nombres de modelo de OpenAI que se atienden con el modelo local por defecto
+const OPENAI_MODEL_ALIASES: &[&str] = &["whisper-1", "gpt-4o-transcribe", "gpt-4o-mini-transcribe"];
+
+/// Estado compartido de los handlers HTTP
+#[derive(Clone)]
+pub struct ApiState {
+    pub service: Arc<TranscriptionService>,
+    /// Modelo para las peticiones que piden un modelo de OpenAI (p. ej. `whisper-1`)
+    pub default_model: String,
+}
+
+/// Construye el router con las rutas de audio de OpenAI
+pub fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/v1/audio/transcriptions", post(transcriptions))
+        .route("/v1/audio/translations", post(translations))
+        .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES))
+        .with_state(state)
+}
+
+/// Valor del campo `response_format`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResponseFormat {
+    Json,
+    Text,
+    Srt,
+    Vtt,
+    VerboseJson,
+}
+
+impl ResponseFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "json" => Some(ResponseFormat::Json),
+            "text" => Some(ResponseFormat::Text),
+            "srt" => Some(ResponseFormat::Srt),
+            "vtt" => Some(ResponseFormat::Vtt),
+            "verbose_json" => Some(ResponseFormat::VerboseJson),
+            _ => None,
+        }
+    }
+}
+
+/// Cuerpo de error con la forma `{"error": {...}}` de OpenAI
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+    param: Option<&'static str>,
+}
+
+impl ApiError {
+    fn invalid_request(message: impl Into<String>, param: Option<&'static str>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message: message.into(),
+            param,
+        }
+    }
+
+    fn not_found(message: impl Into<String>, param: Option<&'static str>) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            message: message.into(),
+            param,
+        }
+    }
+}
+
+impl From<AudioInkError> for ApiError {
+    fn from(err: AudioInkError) -> Self {
+        let status = match err {
+            AudioInkError::ModelNotFound(_) => StatusCode::NOT_FOUND,
+            AudioInkError::UnsupportedFormat(_)
+            | AudioInkError::UnsupportedLanguage(_)
+            | AudioInkError::Audio(_)
+            | AudioInkError::FileError(_) => StatusCode::BAD_REQUEST,
+            AudioInkError::Cancelled => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self {
+            status,
+            message: err.to_string(),
+            param: None,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let error_type = if self.status.is_server_error() {
+            "server_error"
+        } else {
+            "invalid_request_error"
+        };
+        let body = serde_json::json!({
+            "error": {
+                "message": self.message,
+                "type": error_type,
+                "param": self.param,
+                "code": null
+            }
+        });
+        (self.status, Json(body)).into_response()
+    }
+}
+
+/// Campos del formulario de una petición de audio
+#[derive(Debug, Default)]
+struct AudioForm {
+    /// Audio subido, escrito en un archivo temporal que se elimina al soltarlo
+    upload: Option<NamedTempFile>,
+    model: Option<String>,
+    language: Option<String>,
+    prompt: Option<String>,
+    response_format: Option<String>,
+    temperature: Option<String>,
+    timestamp_granularities: Vec<String>,
+}
+
+impl AudioForm {
+    async fn read(mut multipart: Multipart) -> Result<Self, ApiError> {
+        let mut form = AudioForm::default();
+
+        while let Some(field) = multipart
+            .next_field()
+            .await
+            .map_err(|e| ApiError::invalid_request(e.body_text(), None))?
+        {
+            let name = field.name().unwrap_or_default().to_string();
+            if name == "file" {
+                form.upload = Some(save_upload(field).await?);
+                continue;
+            }
+
+            let value = field
+                .text()
+                .await
+                .map_err(|e| ApiError::invalid_request(e.body_text(), None))?;
+            match name.as_str() {
+                "model" => form.model = Some(value),
+                "language" => form.language = Some(value),
+                "prompt" => form.prompt = Some(value),
+                "response_format" => form.response_format = Some(value),
+                "temperature" => form.temperature = Some(value),
+                "timestamp_granularities[]" | "timestamp_granularities" => {
+                    form.timestamp_granularities.push(value)
+                }
+                // El resto de campos de OpenAI se aceptan y se ignoran
+                _ => {}
+            }
+        }
+
+        Ok(form)
+    }
+}
+
+/// Escribe el campo `file` en un archivo temporal a medida que llega
+///
+/// El pipeline trabaja con archivos; así una subida grande no se guarda
+/// entera en memoria.
+async fn save_upload(mut field: Field<'_>) -> Result<NamedTempFile, ApiError> {
+    let file_name = field.file_name().unwrap_or_default().to_string();
+    let extension = Path::new(&file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_lowercase)
+        .filter(|ext| is_supported_format(ext))
+        .ok_or_else(|| {
+            ApiError::invalid_request(
+                format!("Unsupported file format: {}", file_name),
+                Some("file"),
+            )
+        })?;
+
+    let upload = tempfile::Builder::new()
+        .prefix("audioink_upload_")
+        .suffix(&format!(".{}", extension))
+        .tempfile()
+        .map_err(AudioInkError::from)?;
+    let mut writer = tokio::fs::File::from_std(upload.reopen().map_err(AudioInkError::from)?);
+
+    while let Some(chunk) = field
+        .chunk()
+        .await
+        .map_err(|e| ApiError::invalid_request(e.body_text(), Some("file")))?
+    {
+        writer.write_all(&chunk).await.map_err(AudioInkError::from)?;
+    }
+    writer.flush().await.map_err(AudioInkError::from)?;
+
+    Ok(upload)
+}
+
+/// Modelo local para el nombre pedido
+///
+/// Los alias de OpenAI (y un campo vacío) usan el modelo por defecto; un
+/// nombre desconocido es un error en lugar de caer en silencio al por defecto.
+fn resolve_request_model(requested: Option<&str>, default_model: &str) -> Result<String, ApiError> {
+    match requested.map(str::trim).filter(|name| !name.is_empty()) {
+        None => Ok(default_model.to_string()),
+        Some(name) if OPENAI_MODEL_ALIASES.contains(&name) => Ok(default_model.to_string()),
+        Some(name) => match parse_model(name) {
+            Ok(_) => Ok(name.to_string()),
+            Err(e) => Err(ApiError::not_found(e.to_string(), Some("model"))),
+        },
+    }
+}
+
+async fn transcriptions(State(state): State<ApiState>, multipart: Multipart) -> Response {
+    handle_audio_request(state, multipart, Task::Transcribe)
+        .await
+        .unwrap_or_else(IntoResponse::into_response)
+}
+
+async fn translations(State(state): State<ApiState>, multipart: Multipart) -> Response {
+    handle_audio_request(state, multipart, Task::Translate)
+        .await
+        .unwrap_or_else(IntoResponse::into_response)
+}
+
+/// Cancela la transcripción si el cliente se va y se descarta el handler
+struct CancelOnDrop(CancellationToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+async fn handle_audio_request(
+    state: ApiState,
+    multipart: Multipart,
+    task: Task,
+) -> Result<Response, ApiError> {
+    let form = AudioForm::read(multipart).await?;
+
+    let format = match form.response_format.as_deref() {
+        None => ResponseFormat::Json,
+        Some(value) => ResponseFormat::parse(value).ok_or_else(|| {
+            ApiError::invalid_request(
+                format!("Unsupported response_format: {}", value),
+                Some("response_format"),
+            )
+        })?,
+    };
+
+    let mut decoding = DecodingOptions::default();
+    if let Some(value) = form.temperature.as_deref().filter(|t| !t.is_empty()) {
+        decoding.temperature = value
+            .parse::<f32>()
+            .ok()
+            .filter(|t| (0.0..=1.0).contains(t))
+            .ok_or_else(|| {
+                ApiError::invalid_request(
+                    format!("temperature must be a number between 0 and 1: {}", value),
+                    Some("temperature"),
+                )
+            })?;
+    }
+
+    let Some(upload) = form.upload else {
+        return Err(ApiError::invalid_request("Missing required field: file", Some("file")));
+    };
+
+    let model = resolve_request_model(form.model.as_deref(), &state.default_model)?;
+
+    let options = TranscribeOptions {
+        model,
+        language: form
+            .language
+            .filter(|l| !l.is_empty())
+            .unwrap_or_else(|| "auto".to_string()),
+        word_timestamps: format == ResponseFormat::VerboseJson
+            && form.timestamp_granularities.iter().any(|g| g == "word"),
+        task,
+        initial_prompt: form.prompt.filter(|p| !p.trim().is_empty()),
+        decoding,
+        ..TranscribeOptions::default()
+    };
+
+    let mut request = TranscriptionRequest::new(
+        JobSource::File {
+            path: upload.path().to_string_lossy().into_owned(),
+        },
+        options,
+    );
+    request.save_to_history = false;
+
+    let guard = CancelOnDrop(CancellationToken::new());
+    let result = state
+        .service
+        .transcribe(&request, Arc::new(NoopReporter), &guard.0)
+        .await?;
+
+    Ok(render_response(&result, format))
+}
+
+/// Renderiza un resultado en el formato de respuesta pedido
+pub fn render_response(result: &TranscriptionResult, format: ResponseFormat) -> Response {
+    let text = |content_type: &'static str, body: String| {
+        ([(header::CONTENT_TYPE, content_type)], body).into_response()
+    };
+
+    match format {
+        ResponseFormat::Json => Json(serde_json::json!({ "text": result.text })).into_response(),
+        ResponseFormat::Text => text("text/plain; charset=utf-8", result.text.clone()),
+        ResponseFormat::Srt => text("text/plain; charset=utf-8", segments_to_srt(&result.segments)),
+        ResponseFormat::Vtt => text("text/vtt; charset=utf-8", segments_to_vtt(&result.segments)),
+        ResponseFormat::VerboseJson => Json(VerboseResponse::new(result)).into_response(),
+    }
+}
+
+/// Cuerpo de `response_format=verbose_json`
+#[derive(Debug, Serialize)]
+pub struct VerboseResponse {
+    pub task: Task,
+    pub language: String,
+    pub duration: f64,
+    pub text: String,
+    pub segments: Vec<VerboseSegment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub words: Option<Vec<VerboseWord>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerboseSegment {
+    pub id: usize,
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+    pub no_speech_prob: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerboseWord {
+    pub word: String,
+    pub start: f64,
+    pub end: f64,
+}
+
+fn seconds(ms: i64) -> f64 {
+    ms as f64 / 1000.0
+}
+
+impl VerboseResponse {
+    pub fn new(result: &TranscriptionResult) -> Self {
+        let segments = result
+            .segments
+            .iter()
+            .enumerate()
+            .map(|(id, segment)| VerboseSegment {
+                id,
+                start: seconds(segment.start_ms),
+                end: seconds(segment.end_ms),
+                text: segment.text.clone(),
+                no_speech_prob: segment.no_speech_prob.unwrap_or(0.0),
+            })
+            .collect();
+
+        let words: Vec<VerboseWord> = result
+            .segments
+            .iter()
+            .flat_map(|segment: &Segment| segment.words.iter())
+            .map(|word| VerboseWord {
+                word: word.text.trim().to_string(),
+                start: seconds(word.start_ms),
+                end: seconds(word.end_ms),
+            })
+            .collect();
+
+        Self {
+            task: result.task,
+            language: result.language.clone().unwrap_or_default(),
+            duration: result.audio_info.as_ref().map(|a| a.duration).unwrap_or(0.0),
+            text: result.text.clone(),
+            segments,
+            words: if words.is_empty() { None } else { Some(words) },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Word;
+    use crate::persistence::{HistoryManager, SettingsManager};
+    use crate::server::{ApiServer, ServerConfig};
+    use tempfile::TempDir;
+
+    /// Servidor con historial y ajustes en un directorio temporal
+    async fn start_test_server() -> (ApiServer, TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let service = TranscriptionService::new(
+            HistoryManager::with_dir(dir.path().to_path_buf()),
+            Arc::new(SettingsManager::with_file(dir.path().join("settings.json"))),
+        );
+        let config = ServerConfig {
+            port: 0,
+            ..ServerConfig::default()
+        };
+        let server = ApiServer::start(config, Arc::new(service)).await.unwrap();
+        (server, dir)
+    }
+
+    #[tokio::test]
+    async fn test_server_binds_localhost_by_default() {
+        assert!(ServerConfig::default().host.is_loopback());
+
+        let (server, _dir) = start_test_server().await;
+        assert!(server.local_addr().ip().is_loopback());
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_missing_file_returns_openai_error() {
+        let (server, _dir) = start_test_server().await;
+        let url = format!("http://{}/v1/audio/transcriptions", server.local_addr());
+
+        let form = reqwest::multipart::Form::new().text("model", "whisper-1");
+        let response = reqwest::Client::new().post(&url).multipart(form).send().await.unwrap();
+        assert_eq!(response.status(), 400);
+
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"]["type"], "invalid_request_error");
+        assert_eq!(body["error"]["param"], "file");
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unknown_response_format() {
+        let (server, _dir) = start_test_server().await;
+        let url = format!("http://{}/v1/audio/translations", server.local_addr());
+
+        let file = reqwest::multipart::Part::bytes(vec![0u8; 16]).file_name("audio.wav");
+        let form = reqwest::multipart::Form::new()
+            .part("file", file)
+            .text("response_format", "xml");
+        let response = reqwest::Client::new().post(&url).multipart(form).send().await.unwrap();
+        assert_eq!(response.status(), 400);
+
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"]["param"], "response_format");
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_unknown_model_is_rejected() {
+        let (server, _dir) = start_test_server().await;
+        let url = format!("http://{}/v1/audio/transcriptions", server.local_addr());
+
+        let file = reqwest::multipart::Part::bytes(vec![0u8; 16]).file_name("audio.wav");
+        let form = reqwest::multipart::Form::new()
+            .part("file", file)
+            .text("model", "smal");
+        let response = reqwest::Client::new().post(&url).multipart(form).send().await.unwrap();
+        assert_eq!(response.status(), 404);
+
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"]["param"], "model");
+
+        server.stop().await;
+    }
+
+    #[test]
+    fn test_resolve_request_model() {
+        assert_eq!(resolve_request_model(None, "small").unwrap(), "small");
+        assert_eq!(resolve_request_model(Some(" "), "small").unwrap(), "small");
+        assert_eq!(resolve_request_model(Some("whisper-1"), "small").unwrap(), "small");
+        assert_eq!(resolve_request_model(Some("medium"), "small").unwrap(), "medium");
+
+        let err = resolve_request_model(Some("whisper-2"), "small").unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_verbose_response_shape() {
+        let mut segment = Segment::new(1500, 3000, " Hola mundo");
+        segment.words = vec![
+            Word { start_ms: 1500, end_ms: 2000, text: " Hola".to_string(), probability: 0.9 },
+            Word { start_ms: 2000, end_ms: 3000, text: " mundo".to_string(), probability: 0.8 },
+        ];
+        let result = TranscriptionResult {
+            text: "Hola mundo".to_string(),
+            language: Some("es".to_string()),
+            audio_info: None,
+            processing_time: 0.5,
+            segments: vec![segment],
+            task: Task::Transcribe,
+            language_probabilities: Vec::new(),
+            language_spans: Vec::new(),
+            hallucinations: None,
+        };
+
+        let json = serde_json::to_value(VerboseResponse::new(&result)).unwrap();
+        assert_eq!(json["task"], "transcribe");
+        assert_eq!(json["segments"][0]["start"], 1.5);
+        assert_eq!(json["segments"][0]["end"], 3.0);
+        assert_eq!(json["words"][1]["word"], "mundo");
+    }
+}
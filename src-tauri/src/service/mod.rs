@@ -0,0 +1,5 @@
+pub mod progress;
+pub mod transcription;
+
+pub use progress::*;
+pub use transcription::*;
@@ -0,0 +1,18 @@
+//! Progreso del pipeline de transcripción
+//!
+//! El pipeline no sabe quién escucha: la aplicación reenvía los eventos al
+//! webview y el CLI los escribe en stderr.
+
+use crate::models::ProgressEvent;
+
+/// Recibe los eventos de progreso del pipeline
+pub trait ProgressReporter: Send + Sync {
+    fn report(&self, event: ProgressEvent);
+}
+
+/// Reporter que descarta todos los eventos
+pub struct NoopReporter;
+
+impl ProgressReporter for NoopReporter {
+    fn report(&self, _event: ProgressEvent) {}
+}
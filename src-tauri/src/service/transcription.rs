@@ -0,0 +1,440 @@
+//! Pipeline de transcripción
+//!
+//! decodificar → speedup → whisper → historial, compartido por los comandos de
+//! Tauri, la cola de jobs y el binario `audioink-cli`.
+
+use crate::core::{
+    apply_audio_speedup, cleanup_extracted_audio, cleanup_speedup_file, cleanup_youtube_audio,
+    decode_audio_to_whisper_format, download_youtube_audio, extract_audio_from_video,
+    is_model_downloaded, is_video_format, is_ytdlp_available, resolve_model, CancellationToken,
+    EngineOptions, EnginePool, ProgressCallback, WhisperEngine,
+};
+use crate::models::{
+    language_spans, render_segments, AppSettings, AudioInfo, JobSource, Language,
+    LanguageProbability, ProgressEvent, SourceType, TranscribeOptions, TranscriptionEntry,
+    TranscriptionResult, WhisperModel,
+};
+use crate::persistence::{HistoryManager, SettingsManager};
+use crate::service::{NoopReporter, ProgressReporter};
+use crate::utils::{get_ytdlp_install_instructions, AudioInkError, AudioInkResult};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Una ejecución del pipeline
+#[derive(Debug, Clone)]
+pub struct TranscriptionRequest {
+    /// Archivo local o URL a transcribir
+    pub source: JobSource,
+    /// Opciones de transcripción elegidas por el usuario
+    pub options: TranscribeOptions,
+    /// Guardar el resultado en el historial
+    pub save_to_history: bool,
+}
+
+impl TranscriptionRequest {
+    pub fn new(source: JobSource, options: TranscribeOptions) -> Self {
+        Self {
+            source,
+            options,
+            save_to_history: true,
+        }
+    }
+}
+
+/// Pipeline de transcripción con sus motores Whisper cargados
+pub struct TranscriptionService {
+    history_manager: HistoryManager,
+    settings: Arc<SettingsManager>,
+    engines: EnginePool,
+}
+
+/// Archivos temporales creados al preparar el audio
+#[derive(Default)]
+struct TempFiles {
+    downloaded: Option<PathBuf>,
+    extracted: Option<PathBuf>,
+    sped_up: Option<PathBuf>,
+}
+
+impl TempFiles {
+    fn cleanup(&self) {
+        if let Some(ref path) = self.sped_up {
+            cleanup_speedup_file(path);
+        }
+        if let Some(ref path) = self.extracted {
+            cleanup_extracted_audio(path);
+        }
+        if let Some(ref path) = self.downloaded {
+            cleanup_youtube_audio(path);
+        }
+    }
+}
+
+/// Puntos de progreso de los pasos de preparación, según el tipo de origen
+struct ProgressSteps {
+    speedup: f32,
+    decode: f32,
+    load_model: f32,
+}
+
+impl TranscriptionService {
+    pub fn new(history_manager: HistoryManager, settings: Arc<SettingsManager>) -> Self {
+        Self {
+            history_manager,
+            settings,
+            engines: EnginePool::new(),
+        }
+    }
+
+    /// Obtiene el motor Whisper de un modelo, cargándolo en el pool si hace falta
+    pub fn get_or_create_engine(&self, model: &WhisperModel) -> AudioInkResult<Arc<WhisperEngine>> {
+        let budget = self.settings.get().model_memory_budget_bytes();
+        self.engines.get_or_load(model, budget, || WhisperEngine::new(model))
+    }
+
+    /// Modelos cargados en memoria
+    pub fn engines(&self) -> &EnginePool {
+        &self.engines
+    }
+
+    /// Ejecuta el pipeline completo para una petición
+    ///
+    /// Los archivos temporales se eliminan siempre. Devuelve
+    /// `AudioInkError::Cancelled` si se cancela `cancel` en cualquier paso.
+    pub async fn transcribe(
+        &self,
+        request: &TranscriptionRequest,
+        reporter: Arc<dyn ProgressReporter>,
+        cancel: &CancellationToken,
+    ) -> AudioInkResult<TranscriptionResult> {
+        let result = self.run(request, reporter.clone(), cancel).await;
+
+        match &result {
+            Ok(_) => reporter.report(ProgressEvent::Completed {
+                message: "Transcripción completada".to_string(),
+            }),
+            Err(AudioInkError::Cancelled) => reporter.report(ProgressEvent::Cancelled {
+                message: "Transcripción cancelada".to_string(),
+            }),
+            Err(e) => reporter.report(ProgressEvent::Error {
+                message: e.to_string(),
+            }),
+        }
+
+        result
+    }
+
+    async fn run(
+        &self,
+        request: &TranscriptionRequest,
+        reporter: Arc<dyn ProgressReporter>,
+        cancel: &CancellationToken,
+    ) -> AudioInkResult<TranscriptionResult> {
+        let options = &request.options;
+
+        // Parsear opciones
+        let model = parse_model(&options.model)?;
+        let language = parse_language(&options.language)?;
+        let speed = options.speed.clamp(1.0, 2.0); // Limitar a un rango seguro
+
+        // Los modelos .en solo transcriben inglés
+        if model.is_english_only() && language.code().is_some_and(|code| code != "en") {
+            return Err(AudioInkError::UnsupportedLanguage(format!(
+                "{} (el modelo {} solo admite inglés)",
+                options.language, model
+            )));
+        }
+
+        // Verificar que el modelo está descargado
+        if !is_model_downloaded(&model) {
+            return Err(AudioInkError::ModelNotFound(format!(
+                "El modelo '{}' no está descargado. Por favor, descárgalo primero.",
+                model
+            )));
+        }
+
+        let mut temp_files = TempFiles::default();
+        let prepared = self
+            .prepare_audio(&request.source, speed, reporter.as_ref(), cancel, &mut temp_files)
+            .await;
+        temp_files.cleanup();
+        let (samples, audio_info, source_name, steps) = prepared?;
+
+        cancel.check()?;
+
+        // Crear/obtener motor Whisper
+        reporter.report(ProgressEvent::Progress {
+            progress: steps.load_model,
+            message: "Cargando modelo Whisper...".to_string(),
+            chunk_text: None,
+        });
+
+        let engine = self.get_or_create_engine(&model)?;
+
+        // Transcribir
+        let base_progress = steps.load_model;
+        let progress_reporter = reporter.clone();
+        let on_progress: ProgressCallback = Box::new(move |progress, message, chunk_text| {
+            progress_reporter.report(ProgressEvent::Progress {
+                progress: base_progress + progress * (1.0 - base_progress),
+                message,
+                chunk_text,
+            });
+        });
+
+        let engine_options = engine_options(options, language, &self.settings.get());
+        let whisper_cancel = cancel.clone();
+        let mut result = tokio::task::spawn_blocking(move || {
+            engine.transcribe_with_options(
+                &samples,
+                &engine_options,
+                Some(audio_info),
+                Some(on_progress),
+                &whisper_cancel,
+            )
+        })
+        .await
+        .map_err(|e| AudioInkError::Internal(format!("Error de task: {}", e)))??;
+
+        // Ajustar los tiempos si se aceleró el audio
+        if speed > 1.01 {
+            adjust_result_for_speed(&mut result, speed, options.include_timestamps);
+        }
+
+        // Guardar en historial
+        if request.save_to_history {
+            let source_type = match request.source {
+                JobSource::File { .. } => SourceType::Whisper,
+                JobSource::Url { .. } => SourceType::YoutubeWhisper,
+            };
+            let entry = TranscriptionEntry::from_result(source_name, source_type, &result);
+            self.history_manager.save_transcription(entry).await?;
+        }
+
+        Ok(result)
+    }
+
+    /// Detecta el idioma de un archivo local sin transcribirlo
+    /// Devuelve los `top_n` idiomas más probables
+    pub async fn detect_language(
+        &self,
+        file_path: &str,
+        model_name: &str,
+        top_n: usize,
+    ) -> AudioInkResult<Vec<LanguageProbability>> {
+        let model = parse_model(model_name)?;
+        if !is_model_downloaded(&model) {
+            return Err(AudioInkError::ModelNotFound(format!(
+                "El modelo '{}' no está descargado. Por favor, descárgalo primero.",
+                model
+            )));
+        }
+
+        let source = JobSource::File {
+            path: file_path.to_string(),
+        };
+        let cancel = CancellationToken::new();
+        let mut temp_files = TempFiles::default();
+        let prepared = self
+            .prepare_audio(&source, 1.0, &NoopReporter, &cancel, &mut temp_files)
+            .await;
+        temp_files.cleanup();
+        let (samples, _, _, _) = prepared?;
+
+        let engine = self.get_or_create_engine(&model)?;
+        run_blocking(&cancel, move |cancel| engine.detect_language(&samples, top_n, cancel)).await
+    }
+
+    /// Descarga/extrae/acelera/decodifica el audio de origen
+    /// Devuelve los samples, la info del audio en la línea de tiempo original y el nombre del origen
+    async fn prepare_audio(
+        &self,
+        source: &JobSource,
+        speed: f32,
+        reporter: &dyn ProgressReporter,
+        cancel: &CancellationToken,
+        temp_files: &mut TempFiles,
+    ) -> AudioInkResult<(Vec<f32>, AudioInfo, String, ProgressSteps)> {
+        let (base_audio_path, source_name, steps) = match source {
+            JobSource::File { path } => {
+                let path = PathBuf::from(path);
+
+                // Verificar que el archivo existe
+                if !path.exists() {
+                    return Err(AudioInkError::FileError(format!(
+                        "Archivo no encontrado: {}",
+                        path.display()
+                    )));
+                }
+
+                reporter.report(ProgressEvent::Started {
+                    message: "Iniciando transcripción...".to_string(),
+                });
+
+                let source_name = path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("audio")
+                    .to_string();
+
+                // Extraer el audio si es un video
+                let audio_path = if is_video_format(&path) {
+                    reporter.report(ProgressEvent::Progress {
+                        progress: 0.02,
+                        message: "Extrayendo audio del video...".to_string(),
+                        chunk_text: None,
+                    });
+
+                    let extracted = run_blocking(cancel, move |cancel| {
+                        extract_audio_from_video(&path, cancel)
+                    })
+                    .await?;
+                    temp_files.extracted = Some(extracted.clone());
+                    extracted
+                } else {
+                    path
+                };
+
+                let steps = ProgressSteps {
+                    speedup: 0.05,
+                    decode: 0.1,
+                    load_model: 0.2,
+                };
+                (audio_path, source_name, steps)
+            }
+            JobSource::Url { url } => {
+                // Verificar que yt-dlp está disponible
+                if !is_ytdlp_available() {
+                    return Err(AudioInkError::YouTube(
+                        get_ytdlp_install_instructions().to_string(),
+                    ));
+                }
+
+                reporter.report(ProgressEvent::Started {
+                    message: "Downloading audio from YouTube...".to_string(),
+                });
+                reporter.report(ProgressEvent::Progress {
+                    progress: 0.05,
+                    message: "Downloading audio from YouTube...".to_string(),
+                    chunk_text: None,
+                });
+
+                let url = url.clone();
+                let download = run_blocking(cancel, move |cancel| {
+                    download_youtube_audio(&url, cancel)
+                })
+                .await?;
+                temp_files.downloaded = Some(download.audio_path.clone());
+
+                let steps = ProgressSteps {
+                    speedup: 0.15,
+                    decode: 0.2,
+                    load_model: 0.3,
+                };
+                (download.audio_path, download.title, steps)
+            }
+        };
+
+        // Acelerar el audio si hace falta
+        let audio_path = if speed > 1.01 {
+            reporter.report(ProgressEvent::Progress {
+                progress: steps.speedup,
+                message: format!("Acelerando audio a {}x...", speed),
+                chunk_text: None,
+            });
+
+            let sped_up = run_blocking(cancel, move |cancel| {
+                apply_audio_speedup(&base_audio_path, speed, cancel)
+            })
+            .await?;
+            temp_files.sped_up = Some(sped_up.clone());
+            sped_up
+        } else {
+            base_audio_path
+        };
+
+        cancel.check()?;
+
+        // Decodificar audio
+        reporter.report(ProgressEvent::Progress {
+            progress: steps.decode,
+            message: "Decodificando audio...".to_string(),
+            chunk_text: None,
+        });
+
+        let (samples, mut audio_info) =
+            run_blocking(cancel, move |_| decode_audio_to_whisper_format(&audio_path)).await?;
+
+        // Mostrar la duración original en audio_info si se aceleró
+        if speed > 1.01 {
+            audio_info.duration *= speed as f64;
+            audio_info.duration_str = AudioInfo::format_duration(audio_info.duration);
+        }
+
+        Ok((samples, audio_info, source_name, steps))
+    }
+}
+
+impl Default for TranscriptionService {
+    fn default() -> Self {
+        Self::new(HistoryManager::new(), Arc::new(SettingsManager::new()))
+    }
+}
+
+/// Ejecuta un paso bloqueante del pipeline en el pool de hilos bloqueantes
+async fn run_blocking<T, F>(cancel: &CancellationToken, f: F) -> AudioInkResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&CancellationToken) -> AudioInkResult<T> + Send + 'static,
+{
+    let cancel = cancel.clone();
+    tokio::task::spawn_blocking(move || f(&cancel))
+        .await
+        .map_err(|e| AudioInkError::Internal(format!("Error de task: {}", e)))?
+}
+
+/// Construye las opciones del motor a partir de las opciones de la UI y los ajustes
+fn engine_options(
+    options: &TranscribeOptions,
+    language: Language,
+    settings: &AppSettings,
+) -> EngineOptions {
+    EngineOptions {
+        language,
+        include_timestamps: options.include_timestamps,
+        word_timestamps: options.word_timestamps,
+        task: options.task,
+        vad: options.vad,
+        chunk_overlap_secs: options.chunk_overlap_secs,
+        initial_prompt: options.initial_prompt.clone(),
+        language_per_chunk: options.language_per_chunk,
+        decoding: options.decoding,
+        hallucination_filter: options.hallucination_filter,
+        chunk_workers: settings.chunk_workers,
+        n_threads: settings.whisper_threads,
+    }
+}
+
+/// Devuelve los tiempos de los segmentos a la línea de tiempo original tras un
+/// speedup y vuelve a generar el texto y los tramos de idioma
+fn adjust_result_for_speed(result: &mut TranscriptionResult, speed: f32, include_timestamps: bool) {
+    for segment in result.segments.iter_mut() {
+        segment.scale_by(speed);
+    }
+    result.text = render_segments(&result.segments, include_timestamps);
+    result.language_spans = language_spans(&result.segments);
+    if let Some(report) = result.hallucinations.as_mut() {
+        report.scale_by(speed);
+    }
+}
+
+/// Parsea el nombre del modelo
+pub fn parse_model(name: &str) -> AudioInkResult<WhisperModel> {
+    resolve_model(name)
+        .ok_or_else(|| AudioInkError::ModelNotFound(format!("Modelo desconocido: {}", name)))
+}
+
+/// Parsea el idioma (código, nombre en inglés o nombre nativo)
+pub fn parse_language(name: &str) -> AudioInkResult<Language> {
+    Language::parse(name).ok_or_else(|| AudioInkError::UnsupportedLanguage(name.to_string()))
+}
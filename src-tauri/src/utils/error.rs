@@ -18,9 +18,18 @@ pub enum AudioInkError {
     #[error("Error al descargar modelo: {0}")]
     ModelDownload(String),
 
+    #[error("Modelo corrupto: {0}")]
+    ModelCorrupted(String),
+
+    #[error("Espacio en disco insuficiente: {0}")]
+    InsufficientDiskSpace(String),
+
     #[error("Formato de archivo no soportado: {0}")]
     UnsupportedFormat(String),
 
+    #[error("Idioma no soportado: {0}")]
+    UnsupportedLanguage(String),
+
     #[error("Error de archivo: {0}")]
     FileError(String),
 
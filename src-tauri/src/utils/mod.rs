@@ -1,7 +1,9 @@
 pub mod error;
 pub mod platform;
 pub mod subtitle;
+pub mod subtitle_writer;
 
 pub use error::*;
 pub use platform::*;
 pub use subtitle::*;
+pub use subtitle_writer::*;
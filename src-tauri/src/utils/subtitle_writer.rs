@@ -0,0 +1,147 @@
+//! Escritura de subtítulos
+//!
+//! Genera archivos SRT o WebVTT a partir de los segmentos de una transcripción.
+
+use crate::models::Segment;
+use serde::{Deserialize, Serialize};
+
+/// Formatos de subtítulos soportados para exportar
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+}
+
+impl SubtitleFormat {
+    /// Parsea el nombre de un formato ("srt", "vtt" o "webvtt")
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "srt" => Some(SubtitleFormat::Srt),
+            "vtt" | "webvtt" => Some(SubtitleFormat::Vtt),
+            _ => None,
+        }
+    }
+
+    /// Extensión de archivo del formato (sin punto)
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SubtitleFormat::Srt => "srt",
+            SubtitleFormat::Vtt => "vtt",
+        }
+    }
+}
+
+/// Renderiza los segmentos en el formato de subtítulos indicado
+pub fn write_subtitles(segments: &[Segment], format: SubtitleFormat) -> String {
+    match format {
+        SubtitleFormat::Srt => segments_to_srt(segments),
+        SubtitleFormat::Vtt => segments_to_vtt(segments),
+    }
+}
+
+/// Renderiza los segmentos como SRT
+pub fn segments_to_srt(segments: &[Segment]) -> String {
+    let mut output = String::new();
+
+    for (index, (start_ms, end_ms, text)) in subtitle_cues(segments).enumerate() {
+        output.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            index + 1,
+            format_cue_timestamp(start_ms, ','),
+            format_cue_timestamp(end_ms, ','),
+            text
+        ));
+    }
+
+    output
+}
+
+/// Renderiza los segmentos como WebVTT
+pub fn segments_to_vtt(segments: &[Segment]) -> String {
+    let mut output = String::from("WEBVTT\n\n");
+
+    for (start_ms, end_ms, text) in subtitle_cues(segments) {
+        output.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            format_cue_timestamp(start_ms, '.'),
+            format_cue_timestamp(end_ms, '.'),
+            text
+        ));
+    }
+
+    output
+}
+
+/// Devuelve (start_ms, end_ms, text) para cada segmento con texto
+/// Los cues sin duración positiva se alargan para que los reproductores no los descarten
+fn subtitle_cues(segments: &[Segment]) -> impl Iterator<Item = (i64, i64, &str)> {
+    segments
+        .iter()
+        .map(|s| (s.start_ms.max(0), s.end_ms.max(0), s.text.trim()))
+        .filter(|(_, _, text)| !text.is_empty())
+        .map(|(start_ms, end_ms, text)| {
+            let end_ms = if end_ms > start_ms { end_ms } else { start_ms + 1000 };
+            (start_ms, end_ms, text)
+        })
+}
+
+/// Formatea milisegundos como HH:MM:SS<sep>mmm
+fn format_cue_timestamp(ms: i64, millis_separator: char) -> String {
+    let hours = ms / 3_600_000;
+    let minutes = (ms % 3_600_000) / 60_000;
+    let seconds = (ms % 60_000) / 1000;
+    let millis = ms % 1000;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        hours, minutes, seconds, millis_separator, millis
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::clean_subtitle_text;
+
+    fn sample_segments() -> Vec<Segment> {
+        vec![
+            Segment::new(0, 2_500, " Hello world."),
+            Segment::new(2_500, 2_500, "Second line here."),
+            Segment::new(3_725_042, 3_727_000, ""),
+        ]
+    }
+
+    #[test]
+    fn test_segments_to_srt() {
+        let srt = segments_to_srt(&sample_segments());
+        assert_eq!(
+            srt,
+            "1\n00:00:00,000 --> 00:00:02,500\nHello world.\n\n\
+             2\n00:00:02,500 --> 00:00:03,500\nSecond line here.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_segments_to_vtt() {
+        let vtt = segments_to_vtt(&[Segment::new(3_725_042, 3_727_000, "Late cue")]);
+        assert_eq!(vtt, "WEBVTT\n\n01:02:05.042 --> 01:02:07.000\nLate cue\n\n");
+    }
+
+    #[test]
+    fn test_written_subtitles_round_trip_through_cleaner() {
+        for format in [SubtitleFormat::Srt, SubtitleFormat::Vtt] {
+            let output = write_subtitles(&sample_segments(), format);
+            assert_eq!(
+                clean_subtitle_text(&output),
+                "Hello world. Second line here."
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(SubtitleFormat::parse("SRT"), Some(SubtitleFormat::Srt));
+        assert_eq!(SubtitleFormat::parse("webvtt"), Some(SubtitleFormat::Vtt));
+        assert_eq!(SubtitleFormat::parse("txt"), None);
+    }
+}
@@ -7,6 +7,8 @@ const { open, ask } = window.__TAURI__.dialog;
 let activeTab = 'file';
 let isProcessing = false;
 let isDownloading = false;
+let currentDownloadId = null;
+let downloadCancelled = false;
 let settingsOpen = false;
 
 // File tab state
@@ -60,12 +62,55 @@ const elements = {};
 window.addEventListener("DOMContentLoaded", async () => {
     initElements();
     initEventListeners();
+    await loadLanguages();
+    await loadModels();
     applySettings();
     await checkModelStatus();
     await checkYtdlpAvailable();
     await loadHistory();
 });
 
+// Fill the language selector with every language Whisper supports
+async function loadLanguages() {
+    try {
+        const languages = await invoke('get_languages');
+        elements.languageSelect.replaceChildren(...languages.map(lang => {
+            const option = document.createElement('option');
+            option.value = lang.code;
+            option.textContent = lang.name;
+            return option;
+        }));
+    } catch (error) {
+        console.error('Error loading languages:', error);
+    }
+}
+
+// Fill the model selector with the full catalog, including .en and quantized builds,
+// followed by the imported models
+async function loadModels() {
+    try {
+        const models = await invoke('list_models');
+        elements.modelSelect.replaceChildren(...models.map(model => {
+            const option = document.createElement('option');
+            option.value = model.name;
+            const label = model.custom ? model.description : model.name;
+            option.textContent = `${label} (${model.size_formatted})`;
+            option.title = model.description;
+            return option;
+        }));
+    } catch (error) {
+        console.error('Error loading models:', error);
+    }
+}
+
+// Detected language with auto-detect confidence, e.g. "es (92%)"
+function formatLanguage(result) {
+    const language = result.language || '';
+    const top = (result.language_probabilities || [])[0];
+    if (!top || top.code !== language) return language;
+    return `${language} (${Math.round(top.probability * 100)}%)`;
+}
+
 // Check if yt-dlp is available
 async function checkYtdlpAvailable() {
     try {
@@ -149,7 +194,9 @@ function initElements() {
     elements.downloadProgress = document.getElementById('download-progress');
     elements.downloadProgressFill = document.getElementById('download-progress-fill');
     elements.downloadProgressText = document.getElementById('download-progress-text');
+    elements.cancelDownload = document.getElementById('cancel-download');
     elements.downloadedModelsList = document.getElementById('downloaded-models-list');
+    elements.importModelBtn = document.getElementById('import-model-btn');
     elements.includeTimestamps = document.getElementById('include-timestamps');
     elements.timestampsLabel = document.getElementById('timestamps-label');
     elements.timestampsWarning = document.getElementById('timestamps-warning');
@@ -219,6 +266,8 @@ function initEventListeners() {
     // Model
     elements.modelSelect.addEventListener('change', onModelChange);
     elements.downloadModelBtn.addEventListener('click', downloadModel);
+    elements.cancelDownload.addEventListener('click', cancelDownload);
+    elements.importModelBtn.addEventListener('click', importModel);
 
     // Language (save on change)
     elements.languageSelect.addEventListener('change', () => {
@@ -521,6 +570,29 @@ window.deleteModel = async function(modelName) {
     }
 };
 
+async function importModel() {
+    try {
+        const path = await open({
+            multiple: false,
+            filters: [{ name: 'Whisper model', extensions: ['bin', 'gguf'] }]
+        });
+        if (!path) return;
+
+        const fileName = path.split(/[\\/]/).pop().replace(/\.(bin|gguf)$/, '');
+        const displayName = prompt('Model name', fileName);
+        if (!displayName) return;
+
+        const model = await invoke('import_model', { path, displayName });
+        await loadModels();
+        elements.modelSelect.value = model.name;
+        await onModelChange();
+        await loadDownloadedModels();
+    } catch (error) {
+        console.error('Error importing model:', error);
+        alert('Error importing model: ' + error);
+    }
+}
+
 function formatFileSize(bytes) {
     if (bytes < 1024) return bytes + ' B';
     if (bytes < 1024 * 1024) return (bytes / 1024).toFixed(1) + ' KB';
@@ -572,6 +644,7 @@ async function downloadModel() {
 
     try {
         await invoke('download_whisper_model', { modelName: model });
+        currentDownloadId = null;
 
         // Download complete
         elements.downloadProgress.classList.add('hidden');
@@ -580,18 +653,34 @@ async function downloadModel() {
 
         updateTranscribeButton();
     } catch (error) {
-        console.error('Download error:', error);
         elements.downloadProgress.classList.add('hidden');
         elements.downloadModelBtn.classList.remove('hidden');
-        elements.modelStatus.textContent = 'Download failed';
         elements.modelStatus.className = 'model-status not-downloaded';
-        alert('Error downloading model: ' + error);
+        if (downloadCancelled) {
+            elements.modelStatus.textContent = 'Download cancelled';
+        } else {
+            console.error('Download error:', error);
+            elements.modelStatus.textContent = 'Download failed';
+            alert('Error downloading model: ' + error);
+        }
     } finally {
+        currentDownloadId = null;
+        downloadCancelled = false;
         isDownloading = false;
         elements.modelSelect.disabled = false;
     }
 }
 
+async function cancelDownload() {
+    if (!currentDownloadId) return;
+    downloadCancelled = true;
+    try {
+        await invoke('cancel_model_download', { downloadId: currentDownloadId });
+    } catch (error) {
+        console.error('Error cancelling download:', error);
+    }
+}
+
 function updateTranscribeButton() {
     // Check if we have input based on active tab
     let hasInput = false;
@@ -693,7 +782,7 @@ function showResult(result) {
     elements.resultDuration.textContent = result.audio_info ? result.audio_info.duration_str : '';
     elements.resultWords.textContent = `${wordCount} words`;
     elements.resultTime.textContent = `${result.processing_time.toFixed(1)}s`;
-    elements.resultLanguage.textContent = result.language || '';
+    elements.resultLanguage.textContent = formatLanguage(result);
 }
 
 // Clipboard and download
@@ -880,8 +969,14 @@ function setupTauriListeners() {
         }
     });
 
+    listen('model-download-queued', (event) => {
+        if (event.payload.model !== elements.modelSelect.value) return;
+        currentDownloadId = event.payload.id;
+    });
+
     listen('model-download-progress', (event) => {
         const data = event.payload;
+        if (data.id !== currentDownloadId) return;
         elements.downloadProgressFill.style.width = `${data.progress * 100}%`;
         elements.downloadProgressText.textContent = `${data.downloaded_formatted} / ${data.total_formatted}`;
     });